@@ -0,0 +1,105 @@
+//! Stateless checks for [`crate::Tangle::add_batch`]. With the `batch` feature they run in
+//! parallel and signatures go through ed25519 batch verification first.
+
+#[cfg(feature = "batch")]
+use rayon::prelude::*;
+
+#[cfg(feature = "batch")]
+use crate::crypto::{Algorithm, Signature};
+
+use crate::clock::Clock;
+use crate::crypto::PublicKey;
+use crate::validation::ValidationPolicy;
+use crate::Transaction;
+
+pub(crate) fn validate_all(
+    transactions: &[Transaction],
+    policy: &ValidationPolicy,
+    clock: &dyn Clock,
+) -> Vec<bool> {
+    #[cfg(feature = "batch")]
+    let transactions = transactions.par_iter();
+    #[cfg(not(feature = "batch"))]
+    let transactions = transactions.iter();
+
+    transactions
+        .map(|transaction| transaction.validate_with(policy, clock).is_ok())
+        .collect()
+}
+
+/// Whether each transaction is signed by its paired key.
+pub(crate) fn verify_signatures(items: &[(&Transaction, PublicKey)]) -> Vec<bool> {
+    #[cfg(feature = "batch")]
+    {
+        // ed25519 signatures are batched; other schemes are checked one by one.
+        let (batched, others): (Vec<usize>, Vec<usize>) =
+            (0..items.len()).partition(|&index| batchable(&items[index]).is_some());
+        let mut verified = vec![false; items.len()];
+        if verify_batch(&batched, items) {
+            for &index in &batched {
+                verified[index] = true;
+            }
+        } else {
+            // At least one is bad; check them one by one to find out which.
+            verify_indices(&batched, items, &mut verified);
+        }
+        verify_indices(&others, items, &mut verified);
+        verified
+    }
+    #[cfg(not(feature = "batch"))]
+    items
+        .iter()
+        .map(|(transaction, key)| transaction.validate_signature(key).is_ok())
+        .collect()
+}
+
+#[cfg(feature = "batch")]
+fn batchable(
+    (transaction, key): &(&Transaction, PublicKey),
+) -> Option<(ed25519_dalek::Signature, ed25519_dalek::VerifyingKey)> {
+    match (transaction.signature, key) {
+        (Some(Signature::Ed25519(signature)), PublicKey::Ed25519(key))
+            if transaction.algorithm == Algorithm::Ed25519 =>
+        {
+            Some((signature, *key))
+        }
+        _ => None,
+    }
+}
+
+/// Verification is cofactored everywhere (see `crypto::verify_ed25519`), so a passing batch
+/// can stand in for the per-item checks.
+#[cfg(feature = "batch")]
+fn verify_batch(indices: &[usize], items: &[(&Transaction, PublicKey)]) -> bool {
+    if indices.is_empty() {
+        return true;
+    }
+    let mut messages = Vec::with_capacity(indices.len());
+    let mut signatures = Vec::with_capacity(indices.len());
+    let mut keys = Vec::with_capacity(indices.len());
+    for &index in indices {
+        let Some((signature, key)) = batchable(&items[index]) else {
+            return false;
+        };
+        messages.push(items[index].0.serialize());
+        signatures.push(signature);
+        keys.push(key);
+    }
+    let messages: Vec<&[u8]> = messages.iter().map(Vec::as_slice).collect();
+
+    ed25519_dalek::verify_batch(&messages, &signatures, &keys).is_ok()
+}
+
+#[cfg(feature = "batch")]
+fn verify_indices(indices: &[usize], items: &[(&Transaction, PublicKey)], verified: &mut [bool]) {
+    let results: Vec<bool> = indices
+        .par_iter()
+        .map(|&index| {
+            let (transaction, key) = &items[index];
+            transaction.validate_signature(key).is_ok()
+        })
+        .collect();
+    for (&index, result) in indices.iter().zip(results) {
+        verified[index] = result;
+    }
+}
@@ -0,0 +1,379 @@
+//! Signature schemes usable by nodes. Mixed fleets share one tangle: each transaction records
+//! the algorithm it was signed with, and keys and signatures carry their algorithm with them.
+
+use curve25519_dalek::edwards::CompressedEdwardsY;
+use curve25519_dalek::traits::IsIdentity;
+use curve25519_dalek::{EdwardsPoint, Scalar};
+use ed25519_dalek::{Signer as _, Verifier as _};
+use p256::ecdsa;
+use sha2::{Digest, Sha512};
+use zeroize::Zeroizing;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Algorithm {
+    #[default]
+    Ed25519,
+    /// ECDSA over NIST P-256 with SHA-256, as found on most secure elements.
+    EcdsaP256,
+}
+
+impl Algorithm {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Algorithm::Ed25519 => "ed25519",
+            Algorithm::EcdsaP256 => "ecdsaP256",
+        }
+    }
+
+    pub fn parse(algorithm: &str) -> Result<Self, String> {
+        match algorithm {
+            "ed25519" => Ok(Algorithm::Ed25519),
+            "ecdsaP256" => Ok(Algorithm::EcdsaP256),
+            _ => Err(format!("signatureUnknownAlgorithm: {}", algorithm)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PublicKey {
+    Ed25519(ed25519_dalek::VerifyingKey),
+    EcdsaP256(ecdsa::VerifyingKey),
+}
+
+impl PublicKey {
+    pub fn algorithm(&self) -> Algorithm {
+        match self {
+            PublicKey::Ed25519(_) => Algorithm::Ed25519,
+            PublicKey::EcdsaP256(_) => Algorithm::EcdsaP256,
+        }
+    }
+
+    /// Raw key bytes; P-256 keys use the compressed SEC1 encoding.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            PublicKey::Ed25519(key) => key.as_bytes().to_vec(),
+            PublicKey::EcdsaP256(key) => key.to_encoded_point(true).as_bytes().to_vec(),
+        }
+    }
+
+    pub fn from_bytes(algorithm: Algorithm, bytes: &[u8]) -> Result<Self, String> {
+        let invalid = || "invalidPublicKey".to_string();
+        match algorithm {
+            Algorithm::Ed25519 => {
+                let bytes: [u8; 32] = bytes.try_into().map_err(|_| invalid())?;
+                ed25519_dalek::VerifyingKey::from_bytes(&bytes)
+                    .map(PublicKey::Ed25519)
+                    .map_err(|_| invalid())
+            }
+            Algorithm::EcdsaP256 => ecdsa::VerifyingKey::from_sec1_bytes(bytes)
+                .map(PublicKey::EcdsaP256)
+                .map_err(|_| invalid()),
+        }
+    }
+
+    /// `<algorithm>:<hex>`, as carried in registry payloads.
+    pub fn to_hex(&self) -> String {
+        format!(
+            "{}:{}",
+            self.algorithm().as_str(),
+            encode_hex(&self.to_bytes())
+        )
+    }
+
+    pub fn from_hex(key: &str) -> Result<Self, String> {
+        let (algorithm, bytes) = split_hex(key).ok_or_else(|| "invalidPublicKey".to_string())?;
+        Self::from_bytes(algorithm, &bytes)
+    }
+}
+
+impl From<ed25519_dalek::VerifyingKey> for PublicKey {
+    fn from(key: ed25519_dalek::VerifyingKey) -> Self {
+        PublicKey::Ed25519(key)
+    }
+}
+
+impl From<ecdsa::VerifyingKey> for PublicKey {
+    fn from(key: ecdsa::VerifyingKey) -> Self {
+        PublicKey::EcdsaP256(key)
+    }
+}
+
+impl PartialEq<ed25519_dalek::VerifyingKey> for PublicKey {
+    fn eq(&self, other: &ed25519_dalek::VerifyingKey) -> bool {
+        matches!(self, PublicKey::Ed25519(key) if key == other)
+    }
+}
+
+impl PartialEq<ecdsa::VerifyingKey> for PublicKey {
+    fn eq(&self, other: &ecdsa::VerifyingKey) -> bool {
+        matches!(self, PublicKey::EcdsaP256(key) if key == other)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Signature {
+    Ed25519(ed25519_dalek::Signature),
+    EcdsaP256(ecdsa::Signature),
+}
+
+impl Signature {
+    pub fn algorithm(&self) -> Algorithm {
+        match self {
+            Signature::Ed25519(_) => Algorithm::Ed25519,
+            Signature::EcdsaP256(_) => Algorithm::EcdsaP256,
+        }
+    }
+
+    /// 64 bytes for both schemes (`r || s` for ECDSA).
+    pub fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            Signature::Ed25519(signature) => signature.to_bytes().to_vec(),
+            Signature::EcdsaP256(signature) => signature.to_bytes().to_vec(),
+        }
+    }
+
+    pub fn from_bytes(algorithm: Algorithm, bytes: &[u8]) -> Result<Self, String> {
+        let invalid = || "invalidSignature".to_string();
+        match algorithm {
+            Algorithm::Ed25519 => ed25519_dalek::Signature::from_slice(bytes)
+                .map(Signature::Ed25519)
+                .map_err(|_| invalid()),
+            Algorithm::EcdsaP256 => ecdsa::Signature::from_slice(bytes)
+                .map(Signature::EcdsaP256)
+                .map_err(|_| invalid()),
+        }
+    }
+
+    pub fn to_hex(&self) -> String {
+        format!(
+            "{}:{}",
+            self.algorithm().as_str(),
+            encode_hex(&self.to_bytes())
+        )
+    }
+
+    pub fn from_hex(signature: &str) -> Result<Self, String> {
+        let (algorithm, bytes) =
+            split_hex(signature).ok_or_else(|| "invalidSignature".to_string())?;
+        Self::from_bytes(algorithm, &bytes)
+    }
+}
+
+impl From<ed25519_dalek::Signature> for Signature {
+    fn from(signature: ed25519_dalek::Signature) -> Self {
+        Signature::Ed25519(signature)
+    }
+}
+
+impl From<ecdsa::Signature> for Signature {
+    fn from(signature: ecdsa::Signature) -> Self {
+        Signature::EcdsaP256(signature)
+    }
+}
+
+/// Private key of either scheme, e.g. as loaded from a keystore.
+#[derive(Debug, Clone)]
+pub enum SecretKey {
+    Ed25519(ed25519_dalek::SigningKey),
+    EcdsaP256(ecdsa::SigningKey),
+}
+
+impl SecretKey {
+    pub fn generate(algorithm: Algorithm) -> Self {
+        match algorithm {
+            Algorithm::Ed25519 => {
+                SecretKey::Ed25519(ed25519_dalek::SigningKey::generate(&mut rand::rngs::OsRng))
+            }
+            Algorithm::EcdsaP256 => {
+                SecretKey::EcdsaP256(ecdsa::SigningKey::random(&mut rand::rngs::OsRng))
+            }
+        }
+    }
+
+    pub fn to_bytes(&self) -> Zeroizing<Vec<u8>> {
+        Zeroizing::new(match self {
+            SecretKey::Ed25519(key) => key.to_bytes().to_vec(),
+            SecretKey::EcdsaP256(key) => key.to_bytes().to_vec(),
+        })
+    }
+
+    pub fn from_bytes(algorithm: Algorithm, bytes: &[u8]) -> Result<Self, String> {
+        let invalid = || "invalidSecretKey".to_string();
+        match algorithm {
+            Algorithm::Ed25519 => {
+                let bytes: &[u8; 32] = bytes.try_into().map_err(|_| invalid())?;
+                Ok(SecretKey::Ed25519(ed25519_dalek::SigningKey::from_bytes(
+                    bytes,
+                )))
+            }
+            Algorithm::EcdsaP256 => ecdsa::SigningKey::from_slice(bytes)
+                .map(SecretKey::EcdsaP256)
+                .map_err(|_| invalid()),
+        }
+    }
+}
+
+impl From<ed25519_dalek::SigningKey> for SecretKey {
+    fn from(key: ed25519_dalek::SigningKey) -> Self {
+        SecretKey::Ed25519(key)
+    }
+}
+
+impl From<ecdsa::SigningKey> for SecretKey {
+    fn from(key: ecdsa::SigningKey) -> Self {
+        SecretKey::EcdsaP256(key)
+    }
+}
+
+/// Private key able to sign transactions, metadata and endorsements.
+pub trait Signer {
+    fn public_key(&self) -> PublicKey;
+
+    fn sign_message(&self, message: &[u8]) -> Signature;
+
+    fn algorithm(&self) -> Algorithm {
+        self.public_key().algorithm()
+    }
+}
+
+pub trait Verifier {
+    fn algorithm(&self) -> Algorithm;
+
+    fn verify_message(&self, message: &[u8], signature: &Signature) -> Result<(), String>;
+}
+
+impl Signer for ed25519_dalek::SigningKey {
+    fn public_key(&self) -> PublicKey {
+        self.verifying_key().into()
+    }
+
+    fn sign_message(&self, message: &[u8]) -> Signature {
+        self.sign(message).into()
+    }
+}
+
+impl Signer for ecdsa::SigningKey {
+    fn public_key(&self) -> PublicKey {
+        (*self.verifying_key()).into()
+    }
+
+    fn sign_message(&self, message: &[u8]) -> Signature {
+        let signature: ecdsa::Signature = self.sign(message);
+        signature.into()
+    }
+}
+
+impl Signer for SecretKey {
+    fn public_key(&self) -> PublicKey {
+        match self {
+            SecretKey::Ed25519(key) => key.public_key(),
+            SecretKey::EcdsaP256(key) => key.public_key(),
+        }
+    }
+
+    fn sign_message(&self, message: &[u8]) -> Signature {
+        match self {
+            SecretKey::Ed25519(key) => key.sign_message(message),
+            SecretKey::EcdsaP256(key) => key.sign_message(message),
+        }
+    }
+}
+
+impl Verifier for PublicKey {
+    fn algorithm(&self) -> Algorithm {
+        PublicKey::algorithm(self)
+    }
+
+    fn verify_message(&self, message: &[u8], signature: &Signature) -> Result<(), String> {
+        let verified = match (self, signature) {
+            (PublicKey::Ed25519(key), Signature::Ed25519(signature)) => {
+                verify_ed25519(key, message, signature)
+            }
+            (PublicKey::EcdsaP256(key), Signature::EcdsaP256(signature)) => {
+                key.verify(message, signature).is_ok()
+            }
+            _ => {
+                return Err(format!(
+                    "signatureAlgorithmMismatch: {} (key: {})",
+                    signature.algorithm().as_str(),
+                    self.algorithm().as_str()
+                ))
+            }
+        };
+        if verified {
+            Ok(())
+        } else {
+            Err("invalidSignature".to_string())
+        }
+    }
+}
+
+impl Verifier for ed25519_dalek::VerifyingKey {
+    fn algorithm(&self) -> Algorithm {
+        Algorithm::Ed25519
+    }
+
+    fn verify_message(&self, message: &[u8], signature: &Signature) -> Result<(), String> {
+        PublicKey::from(*self).verify_message(message, signature)
+    }
+}
+
+impl Verifier for ecdsa::VerifyingKey {
+    fn algorithm(&self) -> Algorithm {
+        Algorithm::EcdsaP256
+    }
+
+    fn verify_message(&self, message: &[u8], signature: &Signature) -> Result<(), String> {
+        PublicKey::from(*self).verify_message(message, signature)
+    }
+}
+
+/// Cofactored ed25519 verification, `[8][s]B = [8]R + [8][k]A` (RFC 8032, section 5.1.7).
+/// `ed25519_dalek::VerifyingKey::verify` checks the equation without the cofactor, which
+/// disagrees with ed25519-dalek's batch verification on signatures with small-order
+/// components. Any signature in a batch that passes also passes here, so nodes accept the same
+/// signatures whether or not they verified them in a batch.
+pub(crate) fn verify_ed25519(
+    key: &ed25519_dalek::VerifyingKey,
+    message: &[u8],
+    signature: &ed25519_dalek::Signature,
+) -> bool {
+    let Some(s) = Option::<Scalar>::from(Scalar::from_canonical_bytes(*signature.s_bytes())) else {
+        return false;
+    };
+    let (Some(r), Some(a)) = (
+        CompressedEdwardsY(*signature.r_bytes()).decompress(),
+        CompressedEdwardsY(key.to_bytes()).decompress(),
+    ) else {
+        return false;
+    };
+    let hash = Sha512::new()
+        .chain_update(signature.r_bytes())
+        .chain_update(key.as_bytes())
+        .chain_update(message)
+        .finalize();
+    let k = Scalar::from_bytes_mod_order_wide(&hash.into());
+
+    (EdwardsPoint::vartime_double_scalar_mul_basepoint(&k, &-a, &s) - r)
+        .mul_by_cofactor()
+        .is_identity()
+}
+
+fn split_hex(value: &str) -> Option<(Algorithm, Vec<u8>)> {
+    let (algorithm, hex) = value.split_once(':')?;
+    Some((Algorithm::parse(algorithm).ok()?, decode_hex(hex)?))
+}
+
+pub(crate) fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+pub(crate) fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) || !hex.is_ascii() {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+        .collect()
+}
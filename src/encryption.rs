@@ -0,0 +1,237 @@
+//! Payload envelopes readable only by chosen nodes. A random content key encrypts the payload
+//! with ChaCha20-Poly1305 and is wrapped once per recipient under a key agreed over X25519 with
+//! the recipient's ed25519 node key (converted to its Montgomery form). The envelope is what
+//! gets signed and propagated; relaying nodes only see ciphertext.
+//!
+//! Nodes with ECDSA P-256 keys cannot be addressed: sealing for or opening with such a key
+//! fails with `encryptionUnsupportedKey`.
+
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use hkdf::Hkdf;
+use rand::RngCore;
+use sha2::Sha256;
+use x25519_dalek::{PublicKey as X25519PublicKey, StaticSecret};
+
+use crate::crypto::{PublicKey, SecretKey};
+
+pub const ENCRYPTED_MAGIC: u8 = 0xE1;
+pub const ENCRYPTED_VERSION: u8 = 1;
+
+const KEY_LENGTH: usize = 32;
+const NONCE_LENGTH: usize = 12;
+const TAG_LENGTH: usize = 16;
+const WRAPPED_KEY_LENGTH: usize = KEY_LENGTH + TAG_LENGTH;
+const KEY_INFO: &[u8] = b"eco_weave payload key v1";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WrappedKey {
+    pub recipient: String,
+    pub key: [u8; WRAPPED_KEY_LENGTH],
+}
+
+/// Binary layout: magic, version, ephemeral key (32), nonce (12), recipient count (u8), then
+/// per recipient its id length (u8), id and wrapped key (48), then the ciphertext.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EncryptedPayload {
+    pub ephemeral_key: [u8; KEY_LENGTH],
+    pub nonce: [u8; NONCE_LENGTH],
+    pub recipients: Vec<WrappedKey>,
+    pub ciphertext: Vec<u8>,
+}
+
+impl EncryptedPayload {
+    pub fn encrypt(plaintext: &[u8], recipients: &[(&str, &PublicKey)]) -> Result<Self, String> {
+        if recipients.is_empty() {
+            return Err("encryptionNoRecipients".to_string());
+        }
+        if recipients.len() > u8::MAX as usize {
+            return Err("encryptionTooManyRecipients".to_string());
+        }
+
+        // A fresh secret per envelope, reused across its recipients.
+        let ephemeral = StaticSecret::random_from_rng(rand::rngs::OsRng);
+        let ephemeral_key = X25519PublicKey::from(&ephemeral).to_bytes();
+
+        let content_key = random_bytes::<KEY_LENGTH>();
+        let nonce = random_bytes::<NONCE_LENGTH>();
+
+        let mut wrapped = Vec::with_capacity(recipients.len());
+        for (recipient, public_key) in recipients {
+            if recipient.len() > u8::MAX as usize {
+                return Err(format!("encryptionInvalidRecipient: {}", recipient));
+            }
+            let recipient_key = montgomery_key(recipient, public_key)?;
+            let shared = ephemeral.diffie_hellman(&recipient_key);
+            let wrapping_key =
+                derive_key(shared.as_bytes(), &ephemeral_key, recipient_key.as_bytes());
+            let key = ChaCha20Poly1305::new(Key::from_slice(&wrapping_key))
+                .encrypt(
+                    Nonce::from_slice(&[0; NONCE_LENGTH]),
+                    content_key.as_slice(),
+                )
+                .map_err(|_| "encryptionFailed".to_string())?;
+            wrapped.push(WrappedKey {
+                recipient: recipient.to_string(),
+                key: key.try_into().map_err(|_| "encryptionFailed".to_string())?,
+            });
+        }
+
+        let mut payload = Self {
+            ephemeral_key,
+            nonce,
+            recipients: wrapped,
+            ciphertext: Vec::new(),
+        };
+        payload.ciphertext = ChaCha20Poly1305::new(Key::from_slice(&content_key))
+            .encrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: plaintext,
+                    aad: &payload.header(),
+                },
+            )
+            .map_err(|_| "encryptionFailed".to_string())?;
+        Ok(payload)
+    }
+
+    /// Decrypts with the recipient's node key, which must be an ed25519 key.
+    pub fn decrypt(&self, recipient: &str, secret_key: &SecretKey) -> Result<Vec<u8>, String> {
+        let wrapped = self
+            .recipients
+            .iter()
+            .find(|wrapped| wrapped.recipient == recipient)
+            .ok_or_else(|| format!("encryptionNotRecipient: {}", recipient))?;
+
+        let SecretKey::Ed25519(signing_key) = secret_key else {
+            return Err(format!("encryptionUnsupportedKey: {}", recipient));
+        };
+        let secret = StaticSecret::from(signing_key.to_scalar_bytes());
+        let recipient_key = X25519PublicKey::from(&secret);
+        let shared = secret.diffie_hellman(&X25519PublicKey::from(self.ephemeral_key));
+        let wrapping_key = derive_key(
+            shared.as_bytes(),
+            &self.ephemeral_key,
+            recipient_key.as_bytes(),
+        );
+
+        let content_key = ChaCha20Poly1305::new(Key::from_slice(&wrapping_key))
+            .decrypt(
+                Nonce::from_slice(&[0; NONCE_LENGTH]),
+                wrapped.key.as_slice(),
+            )
+            .map_err(|_| "encryptionDecryptFailed".to_string())?;
+        ChaCha20Poly1305::new(Key::from_slice(&content_key))
+            .decrypt(
+                Nonce::from_slice(&self.nonce),
+                Payload {
+                    msg: &self.ciphertext,
+                    aad: &self.header(),
+                },
+            )
+            .map_err(|_| "encryptionDecryptFailed".to_string())
+    }
+
+    /// Everything but the ciphertext, authenticated as associated data.
+    fn header(&self) -> Vec<u8> {
+        let mut header = vec![ENCRYPTED_MAGIC, ENCRYPTED_VERSION];
+        header.extend_from_slice(&self.ephemeral_key);
+        header.extend_from_slice(&self.nonce);
+        header.push(self.recipients.len() as u8);
+        for wrapped in &self.recipients {
+            header.push(wrapped.recipient.len() as u8);
+            header.extend_from_slice(wrapped.recipient.as_bytes());
+            header.extend_from_slice(&wrapped.key);
+        }
+        header
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.header();
+        bytes.extend_from_slice(&self.ciphertext);
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        let mut reader = Reader { bytes };
+        let [magic, version] = reader.take_array()?;
+        if magic != ENCRYPTED_MAGIC {
+            return Err("encryptionInvalidHeader".to_string());
+        }
+        if version != ENCRYPTED_VERSION {
+            return Err(format!("encryptionUnsupportedVersion: {}", version));
+        }
+        let ephemeral_key = reader.take_array()?;
+        let nonce = reader.take_array()?;
+        let [count] = reader.take_array()?;
+
+        let mut recipients = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            let [length] = reader.take_array()?;
+            let recipient = std::str::from_utf8(reader.take(length as usize)?)
+                .map_err(|_| "encryptionInvalidHeader".to_string())?
+                .to_string();
+            recipients.push(WrappedKey {
+                recipient,
+                key: reader.take_array()?,
+            });
+        }
+
+        if reader.bytes.len() < TAG_LENGTH {
+            return Err("encryptionTruncated".to_string());
+        }
+        Ok(Self {
+            ephemeral_key,
+            nonce,
+            recipients,
+            ciphertext: reader.bytes.to_vec(),
+        })
+    }
+
+    pub fn recipients(&self) -> impl Iterator<Item = &str> {
+        self.recipients
+            .iter()
+            .map(|wrapped| wrapped.recipient.as_str())
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, length: usize) -> Result<&'a [u8], String> {
+        if self.bytes.len() < length {
+            return Err("encryptionTruncated".to_string());
+        }
+        let (head, rest) = self.bytes.split_at(length);
+        self.bytes = rest;
+        Ok(head)
+    }
+
+    fn take_array<const N: usize>(&mut self) -> Result<[u8; N], String> {
+        Ok(self.take(N)?.try_into().expect("length checked"))
+    }
+}
+
+fn montgomery_key(recipient: &str, public_key: &PublicKey) -> Result<X25519PublicKey, String> {
+    match public_key {
+        PublicKey::Ed25519(key) => Ok(X25519PublicKey::from(key.to_montgomery().to_bytes())),
+        _ => Err(format!("encryptionUnsupportedKey: {}", recipient)),
+    }
+}
+
+fn derive_key(shared: &[u8], ephemeral_key: &[u8], recipient_key: &[u8]) -> [u8; KEY_LENGTH] {
+    let salt = [ephemeral_key, recipient_key].concat();
+    let mut key = [0; KEY_LENGTH];
+    Hkdf::<Sha256>::new(Some(&salt), shared)
+        .expand(KEY_INFO, &mut key)
+        .expect("32 bytes is a valid HKDF-SHA256 output length");
+    key
+}
+
+fn random_bytes<const N: usize>() -> [u8; N] {
+    let mut bytes = [0; N];
+    rand::rngs::OsRng.fill_bytes(&mut bytes);
+    bytes
+}
@@ -0,0 +1,61 @@
+use crate::crypto::{Algorithm, PublicKey, SecretKey, Signature, Signer};
+use crate::node::StationMetadata;
+use crate::registry::NodeRegistration;
+use crate::Transaction;
+
+/// A node id together with its private key, as stored in a [`crate::keystore::Keystore`].
+#[derive(Debug, Clone)]
+pub struct NodeIdentity {
+    pub node_id: String,
+    key: SecretKey,
+}
+
+impl NodeIdentity {
+    pub fn new(node_id: impl Into<String>, key: impl Into<SecretKey>) -> Self {
+        Self {
+            node_id: node_id.into(),
+            key: key.into(),
+        }
+    }
+
+    pub fn generate(node_id: impl Into<String>, algorithm: Algorithm) -> Self {
+        Self::new(node_id, SecretKey::generate(algorithm))
+    }
+
+    pub fn secret_key(&self) -> &SecretKey {
+        &self.key
+    }
+
+    /// Signs a transaction issued by this node.
+    pub fn sign_transaction(&self, transaction: &mut Transaction) -> Result<(), String> {
+        if transaction.issuer != self.node_id {
+            return Err(format!(
+                "identityIssuerMismatch: {} (identity: {})",
+                transaction.issuer, self.node_id
+            ));
+        }
+        transaction.sign(self);
+        Ok(())
+    }
+
+    pub fn sign_metadata(&self, metadata: &StationMetadata) -> Signature {
+        metadata.sign(&self.node_id, self)
+    }
+
+    /// Self-signed registration announcing this identity to the tangle, mined to
+    /// `difficulty`.
+    pub fn registration(&self, sequence: u64, difficulty: u32) -> Result<Transaction, String> {
+        NodeRegistration::new(self.node_id.clone(), self.public_key())
+            .into_transaction(self, sequence, difficulty)
+    }
+}
+
+impl Signer for NodeIdentity {
+    fn public_key(&self) -> PublicKey {
+        self.key.public_key()
+    }
+
+    fn sign_message(&self, message: &[u8]) -> Signature {
+        self.key.sign_message(message)
+    }
+}
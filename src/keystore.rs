@@ -0,0 +1,265 @@
+//! Signing keys on disk, one file per named identity, encrypted with a key derived from a
+//! passphrase with Argon2id and sealed with ChaCha20-Poly1305.
+
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use argon2::{Argon2, Params, Version};
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroizing;
+
+use crate::crypto::{decode_hex, encode_hex, Algorithm, PublicKey, SecretKey, Signer};
+use crate::identity::NodeIdentity;
+
+const KEYSTORE_VERSION: u8 = 1;
+const FILE_EXTENSION: &str = "key";
+const SALT_LENGTH: usize = 16;
+const NONCE_LENGTH: usize = 12;
+/// Upper bounds on the cost parameters a key file may ask for, so a tampered file cannot make
+/// `load` allocate gigabytes or spin for hours before the passphrase is even checked.
+pub const MAX_KDF_MEMORY_KIB: u32 = 1024 * 1024;
+pub const MAX_KDF_ITERATIONS: u32 = 64;
+pub const MAX_KDF_PARALLELISM: u32 = 16;
+
+/// Argon2id cost parameters, stored with each key so they can be raised later without
+/// breaking existing files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KdfParams {
+    pub memory_kib: u32,
+    pub iterations: u32,
+    pub parallelism: u32,
+}
+
+impl Default for KdfParams {
+    fn default() -> Self {
+        Self {
+            memory_kib: Params::DEFAULT_M_COST,
+            iterations: Params::DEFAULT_T_COST,
+            parallelism: Params::DEFAULT_P_COST,
+        }
+    }
+}
+
+impl KdfParams {
+    pub fn check(&self) -> Result<(), String> {
+        for (name, value, max) in [
+            ("memory_kib", self.memory_kib, MAX_KDF_MEMORY_KIB),
+            ("iterations", self.iterations, MAX_KDF_ITERATIONS),
+            ("parallelism", self.parallelism, MAX_KDF_PARALLELISM),
+        ] {
+            if value > max {
+                return Err(format!(
+                    "keystoreInvalidKdf: {} {} (max: {})",
+                    name, value, max
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn derive_key(&self, passphrase: &str, salt: &[u8]) -> Result<Zeroizing<[u8; 32]>, String> {
+        self.check()?;
+        let params = Params::new(self.memory_kib, self.iterations, self.parallelism, Some(32))
+            .map_err(|error| format!("keystoreInvalidKdf: {}", error))?;
+        let mut key = Zeroizing::new([0; 32]);
+        Argon2::new(argon2::Algorithm::Argon2id, Version::V0x13, params)
+            .hash_password_into(passphrase.as_bytes(), salt, key.as_mut())
+            .map_err(|error| format!("keystoreInvalidKdf: {}", error))?;
+        Ok(key)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct KeyFile {
+    version: u8,
+    node_id: String,
+    public_key: String,
+    kdf: KdfParams,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+impl KeyFile {
+    /// The node id and public key are stored in clear for listing but bound to the ciphertext.
+    fn associated_data(node_id: &str, public_key: &str) -> Vec<u8> {
+        format!("{}:{}", node_id, public_key).into_bytes()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Keystore {
+    pub dir: PathBuf,
+    pub kdf: KdfParams,
+}
+
+impl Keystore {
+    pub fn open(dir: impl AsRef<Path>) -> Result<Self, String> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir).map_err(|error| format!("keystoreIo: {}", error))?;
+        Ok(Self {
+            dir,
+            kdf: KdfParams::default(),
+        })
+    }
+
+    pub fn with_kdf_params(mut self, kdf: KdfParams) -> Self {
+        self.kdf = kdf;
+        self
+    }
+
+    fn path(&self, name: &str) -> Result<PathBuf, String> {
+        if name.is_empty()
+            || !name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(format!("keystoreInvalidName: {}", name));
+        }
+        Ok(self.dir.join(format!("{}.{}", name, FILE_EXTENSION)))
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.path(name).is_ok_and(|path| path.exists())
+    }
+
+    /// Generates an ed25519 identity and stores it under `name`.
+    pub fn generate(
+        &self,
+        name: &str,
+        node_id: impl Into<String>,
+        passphrase: &str,
+    ) -> Result<NodeIdentity, String> {
+        let identity = NodeIdentity::generate(node_id, Algorithm::Ed25519);
+        self.save(name, &identity, passphrase)?;
+        Ok(identity)
+    }
+
+    pub fn save(
+        &self,
+        name: &str,
+        identity: &NodeIdentity,
+        passphrase: &str,
+    ) -> Result<(), String> {
+        let path = self.path(name)?;
+        if path.exists() {
+            return Err(format!("keystoreIdentityExists: {}", name));
+        }
+        self.write(&path, identity, passphrase)
+    }
+
+    fn write(&self, path: &Path, identity: &NodeIdentity, passphrase: &str) -> Result<(), String> {
+        let mut salt = [0; SALT_LENGTH];
+        let mut nonce = [0; NONCE_LENGTH];
+        rand::rngs::OsRng.fill_bytes(&mut salt);
+        rand::rngs::OsRng.fill_bytes(&mut nonce);
+
+        let public_key = identity.public_key().to_hex();
+        let key = self.kdf.derive_key(passphrase, &salt)?;
+        let ciphertext = ChaCha20Poly1305::new(Key::from_slice(key.as_ref()))
+            .encrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: &identity.secret_key().to_bytes(),
+                    aad: &KeyFile::associated_data(&identity.node_id, &public_key),
+                },
+            )
+            .map_err(|_| "keystoreEncryptFailed".to_string())?;
+
+        let file = KeyFile {
+            version: KEYSTORE_VERSION,
+            node_id: identity.node_id.clone(),
+            public_key,
+            kdf: self.kdf,
+            salt: encode_hex(&salt),
+            nonce: encode_hex(&nonce),
+            ciphertext: encode_hex(&ciphertext),
+        };
+        let contents = serde_json::to_string_pretty(&file).expect("key file serializes to JSON");
+
+        // Write next to the target and rename, so a crash never leaves a truncated key.
+        let tmp = path.with_extension("tmp");
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        options
+            .open(&tmp)
+            .and_then(|mut out| out.write_all(contents.as_bytes()))
+            .and_then(|_| fs::rename(&tmp, path))
+            .map_err(|error| format!("keystoreIo: {}", error))
+    }
+
+    pub fn load(&self, name: &str, passphrase: &str) -> Result<NodeIdentity, String> {
+        let path = self.path(name)?;
+        let contents = fs::read_to_string(&path).map_err(|error| match error.kind() {
+            std::io::ErrorKind::NotFound => format!("keystoreUnknownIdentity: {}", name),
+            _ => format!("keystoreIo: {}", error),
+        })?;
+        let corrupt = |reason: &str| format!("keystoreCorrupt: {} ({})", name, reason);
+
+        let file: KeyFile =
+            serde_json::from_str(&contents).map_err(|error| corrupt(&error.to_string()))?;
+        if file.version != KEYSTORE_VERSION {
+            return Err(format!("keystoreUnsupportedVersion: {}", file.version));
+        }
+        let public_key = PublicKey::from_hex(&file.public_key).map_err(|error| corrupt(&error))?;
+        let salt = decode_hex(&file.salt).ok_or_else(|| corrupt("salt"))?;
+        let nonce: [u8; NONCE_LENGTH] = decode_hex(&file.nonce)
+            .and_then(|nonce| nonce.try_into().ok())
+            .ok_or_else(|| corrupt("nonce"))?;
+        let ciphertext = decode_hex(&file.ciphertext).ok_or_else(|| corrupt("ciphertext"))?;
+
+        let key = file.kdf.derive_key(passphrase, &salt)?;
+        let secret = Zeroizing::new(
+            ChaCha20Poly1305::new(Key::from_slice(key.as_ref()))
+                .decrypt(
+                    Nonce::from_slice(&nonce),
+                    Payload {
+                        msg: &ciphertext,
+                        aad: &KeyFile::associated_data(&file.node_id, &file.public_key),
+                    },
+                )
+                .map_err(|_| "keystoreWrongPassphrase".to_string())?,
+        );
+
+        let secret = SecretKey::from_bytes(public_key.algorithm(), &secret)
+            .map_err(|error| corrupt(&error))?;
+        if secret.public_key() != public_key {
+            return Err(corrupt("public key"));
+        }
+        Ok(NodeIdentity::new(file.node_id, secret))
+    }
+
+    /// Names of stored identities, sorted.
+    pub fn list(&self) -> Result<Vec<String>, String> {
+        let entries = fs::read_dir(&self.dir).map_err(|error| format!("keystoreIo: {}", error))?;
+        let mut names: Vec<String> = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == FILE_EXTENSION))
+            .filter_map(|path| path.file_stem()?.to_str().map(str::to_string))
+            .collect();
+        names.sort();
+        Ok(names)
+    }
+
+    pub fn remove(&self, name: &str) -> Result<(), String> {
+        fs::remove_file(self.path(name)?).map_err(|error| match error.kind() {
+            std::io::ErrorKind::NotFound => format!("keystoreUnknownIdentity: {}", name),
+            _ => format!("keystoreIo: {}", error),
+        })
+    }
+
+    pub fn change_passphrase(&self, name: &str, old: &str, new: &str) -> Result<(), String> {
+        let identity = self.load(name, old)?;
+        self.write(&self.path(name)?, &identity, new)
+    }
+}
@@ -1,8 +1,22 @@
+mod batch;
+pub mod clock;
+pub mod crypto;
+pub mod encryption;
+pub mod identity;
+pub mod keystore;
 pub mod node;
+pub mod rate_limit;
+pub mod registry;
 pub mod tangle;
 pub mod transaction;
 pub mod validation;
 
-pub use node::Node;
-pub use tangle::Tangle;
-pub use transaction::Transaction;
+pub use clock::{Clock, ManualClock, SystemClock};
+pub use crypto::{Algorithm, PublicKey, SecretKey, Signature, Signer, Verifier};
+pub use identity::NodeIdentity;
+pub use keystore::Keystore;
+pub use node::{BoundingBox, KeyRecord, Node, StationMetadata, ThresholdPolicy};
+pub use rate_limit::{RateLimit, RateLimitError, RateLimiter};
+pub use registry::{KeyRotation, NodeRegistration, Revocation};
+pub use tangle::{Tangle, TopologyEvent, TransactionError};
+pub use transaction::{Cosignature, Transaction, TransactionKind};
@@ -1,18 +1,203 @@
-use ed25519_dalek::VerifyingKey;
+use serde::{Deserialize, Serialize};
 
+use crate::crypto::{PublicKey, Signature, Signer, Verifier};
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct StationMetadata {
+    /// Degrees, WGS84.
+    pub latitude: Option<f64>,
+    /// Degrees, WGS84.
+    pub longitude: Option<f64>,
+    /// Meters above sea level.
+    pub altitude: Option<f64>,
+    pub sensor_model: Option<String>,
+    /// Milliseconds since the Unix epoch.
+    pub installed_at: Option<u64>,
+    pub capabilities: Vec<String>,
+}
+
+impl StationMetadata {
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(latitude) = self.latitude {
+            if !(-90.0..=90.0).contains(&latitude) {
+                return Err(format!("metadataInvalidLatitude: {}", latitude));
+            }
+        }
+        if let Some(longitude) = self.longitude {
+            if !(-180.0..=180.0).contains(&longitude) {
+                return Err(format!("metadataInvalidLongitude: {}", longitude));
+            }
+        }
+        if self.latitude.is_some() != self.longitude.is_some() {
+            return Err("metadataIncompleteLocation".to_string());
+        }
+        Ok(())
+    }
+
+    pub fn location(&self) -> Option<(f64, f64)> {
+        self.latitude.zip(self.longitude)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("metadata serializes to JSON")
+    }
+
+    pub fn from_json(metadata: &str) -> Result<Self, String> {
+        serde_json::from_str(metadata).map_err(|_| "metadataInvalidJson".to_string())
+    }
+
+    fn serialize(&self, node_id: &str) -> Vec<u8> {
+        format!("metadata:{}:{}", node_id, self.to_json()).into_bytes()
+    }
+
+    pub fn sign<S: Signer + ?Sized>(&self, node_id: &str, signing_key: &S) -> Signature {
+        signing_key.sign_message(&self.serialize(node_id))
+    }
+
+    pub fn verify<V: Verifier + ?Sized>(
+        &self,
+        node_id: &str,
+        verifying_key: &V,
+        signature: &Signature,
+    ) -> Result<(), String> {
+        verifying_key
+            .verify_message(&self.serialize(node_id), signature)
+            .map_err(|_| "metadataInvalidSignature".to_string())
+    }
+}
+
+/// Latitude/longitude rectangle. A box whose `min_longitude` is greater than its
+/// `max_longitude` wraps across the antimeridian.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoundingBox {
+    pub min_latitude: f64,
+    pub min_longitude: f64,
+    pub max_latitude: f64,
+    pub max_longitude: f64,
+}
+
+impl BoundingBox {
+    pub fn new(
+        min_latitude: f64,
+        min_longitude: f64,
+        max_latitude: f64,
+        max_longitude: f64,
+    ) -> Self {
+        Self {
+            min_latitude,
+            min_longitude,
+            max_latitude,
+            max_longitude,
+        }
+    }
+
+    pub fn contains(&self, latitude: f64, longitude: f64) -> bool {
+        let within_latitude = (self.min_latitude..=self.max_latitude).contains(&latitude);
+        let within_longitude = if self.min_longitude <= self.max_longitude {
+            (self.min_longitude..=self.max_longitude).contains(&longitude)
+        } else {
+            longitude >= self.min_longitude || longitude <= self.max_longitude
+        };
+        within_latitude && within_longitude
+    }
+}
+
+/// A key the node has signed with, valid for timestamps in `[valid_from, valid_until)`.
 #[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyRecord {
+    pub verifying_key: PublicKey,
+    pub valid_from: u64,
+    pub valid_until: Option<u64>,
+    /// Key that signed the rotation installing this one; `None` for the initial key and
+    /// authority replacements.
+    pub rotated_by: Option<PublicKey>,
+    /// Compromise time of the revocation that ended this key.
+    pub revoked_at: Option<u64>,
+}
+
+impl KeyRecord {
+    pub fn is_valid_at(&self, timestamp: u64) -> bool {
+        timestamp >= self.valid_from && self.valid_until.is_none_or(|until| timestamp < until)
+    }
+}
+
+/// M-of-N cosigning requirement on a node's transactions, on top of its own signature.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ThresholdPolicy {
+    pub threshold: usize,
+    /// Node ids allowed to cosign.
+    pub signers: Vec<String>,
+    /// Restricts the policy to transactions tagged with this schema.
+    pub schema: Option<String>,
+}
+
+impl ThresholdPolicy {
+    /// Fails on the same policies as [`ThresholdPolicy::validate`], including duplicate signers.
+    pub fn new(threshold: usize, signers: Vec<String>) -> Result<Self, String> {
+        let policy = Self {
+            threshold,
+            signers,
+            schema: None,
+        };
+        policy.validate()?;
+        Ok(policy)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        let mut seen = std::collections::HashSet::new();
+        if let Some(signer) = self.signers.iter().find(|signer| !seen.insert(*signer)) {
+            return Err(format!("thresholdDuplicateSigner: {}", signer));
+        }
+        if self.threshold == 0 || self.threshold > self.signers.len() {
+            return Err(format!(
+                "thresholdInvalid: {} (signers: {})",
+                self.threshold,
+                self.signers.len()
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn for_schema(mut self, schema: impl Into<String>) -> Self {
+        self.schema = Some(schema.into());
+        self
+    }
+
+    pub fn applies_to(&self, schema: Option<&str>) -> bool {
+        self.schema.is_none() || self.schema.as_deref() == schema
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct Node {
     pub id: String,
     pub neighbors: Vec<String>,
-    pub verifying_key: VerifyingKey,
+    /// Most recently installed key. Older and revoked keys are kept in `keys`; see
+    /// [`Node::current_key`] for the key the node may sign with now.
+    pub verifying_key: PublicKey,
+    pub keys: Vec<KeyRecord>,
+    pub metadata: Option<StationMetadata>,
+    pub metadata_signature: Option<Signature>,
+    pub threshold: Option<ThresholdPolicy>,
 }
 
 impl Node {
-    pub fn new(id: impl Into<String>, verifying_key: VerifyingKey) -> Self {
+    pub fn new(id: impl Into<String>, verifying_key: impl Into<PublicKey>) -> Self {
+        let verifying_key = verifying_key.into();
         Self {
             id: id.into(),
             neighbors: Vec::new(),
             verifying_key,
+            keys: vec![KeyRecord {
+                verifying_key,
+                valid_from: 0,
+                valid_until: None,
+                rotated_by: None,
+                revoked_at: None,
+            }],
+            metadata: None,
+            metadata_signature: None,
+            threshold: None,
         }
     }
 
@@ -26,7 +211,146 @@ impl Node {
         }
     }
 
+    pub fn remove_neighbor(&mut self, neighbor_id: &str) -> bool {
+        let before = self.neighbors.len();
+        self.neighbors.retain(|id| id != neighbor_id);
+        self.neighbors.len() != before
+    }
+
     pub fn is_neighbor(&self, neighbor_id: &str) -> bool {
         self.neighbors.contains(&neighbor_id.to_string())
     }
+
+    /// Attaches metadata signed by the node's key valid at `at`.
+    pub fn set_metadata(
+        &mut self,
+        metadata: StationMetadata,
+        signature: Signature,
+        at: u64,
+    ) -> Result<(), String> {
+        metadata.validate()?;
+        let verifying_key = self
+            .key_at(at)
+            .filter(|key| !self.is_revoked(key))
+            .ok_or_else(|| format!("nodeKeyRevoked: {}", self.id))?;
+        metadata.verify(&self.id, verifying_key, &signature)?;
+        self.metadata = Some(metadata);
+        self.metadata_signature = Some(signature);
+        Ok(())
+    }
+
+    pub fn location(&self) -> Option<(f64, f64)> {
+        self.metadata.as_ref().and_then(StationMetadata::location)
+    }
+
+    /// The latest key, unless it has been revoked and nothing replaced it.
+    pub fn current_key(&self) -> Option<&PublicKey> {
+        Some(&self.verifying_key).filter(|key| !self.is_revoked(key))
+    }
+
+    /// Key that was valid when `timestamp` was issued, if any.
+    pub fn key_at(&self, timestamp: u64) -> Option<&PublicKey> {
+        self.keys
+            .iter()
+            .rev()
+            .find(|record| record.is_valid_at(timestamp))
+            .map(|record| &record.verifying_key)
+    }
+
+    pub fn is_revoked(&self, key: &PublicKey) -> bool {
+        self.keys
+            .iter()
+            .any(|record| &record.verifying_key == key && record.revoked_at.is_some())
+    }
+
+    pub fn check_rotation(&self, new_key: &PublicKey, at: u64) -> Result<(), String> {
+        if self
+            .keys
+            .iter()
+            .any(|record| &record.verifying_key == new_key)
+        {
+            return Err("keyRotationReused".to_string());
+        }
+        let latest_revocation = self
+            .keys
+            .iter()
+            .filter_map(|record| record.revoked_at)
+            .max();
+        if latest_revocation.is_some_and(|revoked_at| at < revoked_at) {
+            return Err(format!("keyRotationBeforeRevocation: {}", at));
+        }
+        // Revoked keys are bounded by their revocation instead, so a key installed by a
+        // rotation that has since been undone does not hold back the replacement.
+        let latest = self
+            .keys
+            .iter()
+            .filter(|record| record.revoked_at.is_none())
+            .map(|record| record.valid_from)
+            .max();
+        if latest.is_some_and(|valid_from| at < valid_from) {
+            return Err(format!("keyRotationOutOfOrder: {}", at));
+        }
+        Ok(())
+    }
+
+    /// Replaces the current key from `at` onwards.
+    pub fn rotate_key(&mut self, new_key: impl Into<PublicKey>, at: u64) -> Result<(), String> {
+        let new_key = new_key.into();
+        self.check_rotation(&new_key, at)?;
+        let rotated_by = self.key_at(at).copied();
+        for record in &mut self.keys {
+            if record.valid_until.is_none_or(|until| until > at) {
+                record.valid_until = Some(at);
+            }
+        }
+        self.keys.push(KeyRecord {
+            verifying_key: new_key,
+            valid_from: at,
+            valid_until: None,
+            rotated_by,
+            revoked_at: None,
+        });
+        self.verifying_key = new_key;
+        Ok(())
+    }
+
+    /// Invalidates `key` for anything issued at or after `compromised_at`. Keys installed by
+    /// rotations that `key` signed from then on were installed by whoever held the leaked key,
+    /// so they are invalidated entirely, along with anything they rotated to in turn.
+    pub fn revoke_key(&mut self, key: &PublicKey, compromised_at: u64) -> bool {
+        if !self.keys.iter().any(|record| &record.verifying_key == key) {
+            return false;
+        }
+
+        let mut pending = vec![(*key, compromised_at)];
+        while let Some((key, from)) = pending.pop() {
+            for record in &mut self.keys {
+                if record.verifying_key == key {
+                    record.valid_until =
+                        Some(record.valid_until.map_or(from, |until| until.min(from)));
+                    record.revoked_at = Some(
+                        record
+                            .revoked_at
+                            .map_or(compromised_at, |at| at.min(compromised_at)),
+                    );
+                } else if record.rotated_by == Some(key)
+                    && record.valid_from >= from
+                    && record.valid_until != Some(record.valid_from)
+                {
+                    pending.push((record.verifying_key, record.valid_from));
+                }
+            }
+        }
+
+        // Fall back to the newest key that is still open, if the revocation left one.
+        if let Some(record) = self
+            .keys
+            .iter()
+            .rev()
+            .find(|record| record.revoked_at.is_none() && record.valid_until.is_none())
+        {
+            self.verifying_key = record.verifying_key;
+        }
+        true
+    }
 }
@@ -0,0 +1,201 @@
+//! Token buckets capping how many transactions each issuer may insert and how fast each
+//! neighbor may gossip to us. Time comes from the tangle's clock.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+/// `burst` transactions at once, refilled at `per_second`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimit {
+    pub burst: u32,
+    pub per_second: f64,
+}
+
+impl RateLimit {
+    pub fn new(burst: u32, per_second: f64) -> Self {
+        Self { burst, per_second }
+    }
+
+    pub fn per_minute(burst: u32, per_minute: f64) -> Self {
+        Self::new(burst, per_minute / 60.0)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenBucket {
+    pub tokens: f64,
+    pub updated_at: u64,
+}
+
+impl TokenBucket {
+    pub fn full(limit: &RateLimit, now: u64) -> Self {
+        Self {
+            tokens: limit.burst as f64,
+            updated_at: now,
+        }
+    }
+
+    /// Takes one token, or returns how many milliseconds until one is available.
+    pub fn try_take(&mut self, limit: &RateLimit, now: u64) -> Result<(), u64> {
+        let elapsed = now.saturating_sub(self.updated_at);
+        self.tokens =
+            (self.tokens + elapsed as f64 * limit.per_second / 1000.0).min(limit.burst as f64);
+        self.updated_at = self.updated_at.max(now);
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            return Ok(());
+        }
+        if limit.per_second <= 0.0 {
+            return Err(u64::MAX);
+        }
+        Err(((1.0 - self.tokens) * 1000.0 / limit.per_second).ceil() as u64)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RateLimitScope {
+    /// Transactions issued by a node, however they reached us.
+    Issuer,
+    /// Transactions gossiped to us by a neighbor.
+    Neighbor,
+}
+
+impl RateLimitScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RateLimitScope::Issuer => "issuer",
+            RateLimitScope::Neighbor => "neighbor",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RateLimitError {
+    pub scope: RateLimitScope,
+    pub node_id: String,
+    pub retry_after_ms: u64,
+}
+
+impl fmt::Display for RateLimitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "transactionRateLimited: {} {} (retry after: {} ms)",
+            self.scope.as_str(),
+            self.node_id,
+            self.retry_after_ms
+        )
+    }
+}
+
+impl std::error::Error for RateLimitError {}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RateLimitMetrics {
+    pub allowed: u64,
+    pub limited_issuers: u64,
+    pub limited_neighbors: u64,
+    /// Rejections per node id, across both scopes.
+    pub limited_by_node: BTreeMap<String, u64>,
+}
+
+/// No limits apply until configured.
+#[derive(Debug, Clone, Default)]
+pub struct RateLimiter {
+    pub issuer_limit: Option<RateLimit>,
+    pub neighbor_limit: Option<RateLimit>,
+    /// Per-node issuer limits replacing `issuer_limit`, e.g. for a gateway's own stations.
+    pub node_limits: HashMap<String, RateLimit>,
+    issuers: HashMap<String, TokenBucket>,
+    neighbors: HashMap<String, TokenBucket>,
+    metrics: RateLimitMetrics,
+}
+
+impl RateLimiter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_issuer_limit(mut self, limit: RateLimit) -> Self {
+        self.issuer_limit = Some(limit);
+        self
+    }
+
+    pub fn with_neighbor_limit(mut self, limit: RateLimit) -> Self {
+        self.neighbor_limit = Some(limit);
+        self
+    }
+
+    pub fn set_node_limit(&mut self, node_id: impl Into<String>, limit: RateLimit) {
+        let node_id = node_id.into();
+        self.issuers.remove(&node_id);
+        self.node_limits.insert(node_id, limit);
+    }
+
+    pub fn check_issuer(&mut self, node_id: &str, now: u64) -> Result<(), RateLimitError> {
+        let limit = self
+            .node_limits
+            .get(node_id)
+            .or(self.issuer_limit.as_ref())
+            .copied();
+        self.check(RateLimitScope::Issuer, limit, node_id, now)
+    }
+
+    pub fn check_neighbor(&mut self, node_id: &str, now: u64) -> Result<(), RateLimitError> {
+        self.check(RateLimitScope::Neighbor, self.neighbor_limit, node_id, now)
+    }
+
+    fn check(
+        &mut self,
+        scope: RateLimitScope,
+        limit: Option<RateLimit>,
+        node_id: &str,
+        now: u64,
+    ) -> Result<(), RateLimitError> {
+        let Some(limit) = limit else {
+            return Ok(());
+        };
+        let buckets = match scope {
+            RateLimitScope::Issuer => &mut self.issuers,
+            RateLimitScope::Neighbor => &mut self.neighbors,
+        };
+        let result = buckets
+            .entry(node_id.to_string())
+            .or_insert_with(|| TokenBucket::full(&limit, now))
+            .try_take(&limit, now);
+
+        match result {
+            Ok(()) => {
+                self.metrics.allowed += 1;
+                Ok(())
+            }
+            Err(retry_after_ms) => {
+                match scope {
+                    RateLimitScope::Issuer => self.metrics.limited_issuers += 1,
+                    RateLimitScope::Neighbor => self.metrics.limited_neighbors += 1,
+                }
+                *self
+                    .metrics
+                    .limited_by_node
+                    .entry(node_id.to_string())
+                    .or_default() += 1;
+                Err(RateLimitError {
+                    scope,
+                    node_id: node_id.to_string(),
+                    retry_after_ms,
+                })
+            }
+        }
+    }
+
+    pub fn metrics(&self) -> &RateLimitMetrics {
+        &self.metrics
+    }
+
+    /// Drops buckets for a node, e.g. once it is removed from the tangle.
+    pub fn forget(&mut self, node_id: &str) {
+        self.issuers.remove(node_id);
+        self.neighbors.remove(node_id);
+    }
+}
@@ -0,0 +1,319 @@
+use serde::{Deserialize, Serialize};
+
+use crate::crypto::{PublicKey, Signature, Signer, Verifier};
+use crate::node::{Node, StationMetadata};
+use crate::transaction::{Transaction, TransactionKind};
+use crate::validation::ValidationPolicy;
+
+/// Authority signature vouching for a node id / key pair.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Endorsement {
+    pub authority: String,
+    pub signature: Signature,
+}
+
+/// Payload of a [`TransactionKind::Registration`] transaction. The transaction carrying it
+/// must be issued by `node_id` and signed with `verifying_key`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NodeRegistration {
+    pub node_id: String,
+    pub verifying_key: PublicKey,
+    pub metadata: Option<StationMetadata>,
+    pub metadata_signature: Option<Signature>,
+    pub endorsement: Option<Endorsement>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RegistrationPayload {
+    node_id: String,
+    public_key: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    metadata: Option<StationMetadata>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    metadata_signature: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    authority: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    endorsement: Option<String>,
+}
+
+impl NodeRegistration {
+    pub fn new(node_id: impl Into<String>, verifying_key: impl Into<PublicKey>) -> Self {
+        Self {
+            node_id: node_id.into(),
+            verifying_key: verifying_key.into(),
+            metadata: None,
+            metadata_signature: None,
+            endorsement: None,
+        }
+    }
+
+    pub fn with_metadata(mut self, metadata: StationMetadata, signature: Signature) -> Self {
+        self.metadata = Some(metadata);
+        self.metadata_signature = Some(signature);
+        self
+    }
+
+    fn endorsement_message(&self) -> Vec<u8> {
+        format!("endorse:{}:{}", self.node_id, self.verifying_key.to_hex()).into_bytes()
+    }
+
+    pub fn endorse<S: Signer + ?Sized>(
+        mut self,
+        authority: impl Into<String>,
+        authority_key: &S,
+    ) -> Self {
+        self.endorsement = Some(Endorsement {
+            authority: authority.into(),
+            signature: authority_key.sign_message(&self.endorsement_message()),
+        });
+        self
+    }
+
+    pub fn verify_endorsement<V: Verifier + ?Sized>(
+        &self,
+        authority_key: &V,
+    ) -> Result<(), String> {
+        let endorsement = self
+            .endorsement
+            .as_ref()
+            .ok_or_else(|| "registrationNotEndorsed".to_string())?;
+        authority_key
+            .verify_message(&self.endorsement_message(), &endorsement.signature)
+            .map_err(|_| format!("registrationInvalidEndorsement: {}", endorsement.authority))
+    }
+
+    pub fn to_json(&self) -> String {
+        let payload = RegistrationPayload {
+            node_id: self.node_id.clone(),
+            public_key: self.verifying_key.to_hex(),
+            metadata: self.metadata.clone(),
+            metadata_signature: self.metadata_signature.map(|signature| signature.to_hex()),
+            authority: self
+                .endorsement
+                .as_ref()
+                .map(|endorsement| endorsement.authority.clone()),
+            endorsement: self
+                .endorsement
+                .as_ref()
+                .map(|endorsement| endorsement.signature.to_hex()),
+        };
+        serde_json::to_string(&payload).expect("registration serializes to JSON")
+    }
+
+    pub fn from_json(payload: &[u8]) -> Result<Self, String> {
+        let payload: RegistrationPayload = serde_json::from_slice(payload)
+            .map_err(|error| format!("registrationInvalidPayload: {}", error))?;
+
+        let verifying_key = PublicKey::from_hex(&payload.public_key)?;
+
+        let metadata_signature = payload
+            .metadata_signature
+            .as_deref()
+            .map(decode_signature)
+            .transpose()?;
+
+        let endorsement = match (payload.authority, payload.endorsement.as_deref()) {
+            (Some(authority), Some(signature)) => Some(Endorsement {
+                authority,
+                signature: decode_signature(signature)?,
+            }),
+            (None, None) => None,
+            _ => return Err("registrationIncompleteEndorsement".to_string()),
+        };
+
+        Ok(Self {
+            node_id: payload.node_id,
+            verifying_key,
+            metadata: payload.metadata,
+            metadata_signature,
+            endorsement,
+        })
+    }
+
+    /// Checks everything that does not depend on tangle state: metadata validity and its
+    /// signature by the registered key.
+    pub fn validate(&self) -> Result<(), String> {
+        match (&self.metadata, &self.metadata_signature) {
+            (Some(metadata), Some(signature)) => {
+                metadata.validate()?;
+                metadata.verify(&self.node_id, &self.verifying_key, signature)
+            }
+            (None, None) => Ok(()),
+            _ => Err("registrationIncompleteMetadata".to_string()),
+        }
+    }
+
+    /// Builds the self-signed registration transaction, mined to `difficulty` so it passes a
+    /// tangle's proof-of-work policy. `signing_key` must match `verifying_key`.
+    pub fn into_transaction<S: Signer + ?Sized>(
+        self,
+        signing_key: &S,
+        sequence: u64,
+        difficulty: u32,
+    ) -> Result<Transaction, String> {
+        if signing_key.public_key() != self.verifying_key {
+            return Err("registrationKeyMismatch".to_string());
+        }
+
+        let mut transaction = Transaction::new_with_kind(
+            format!("register-{}-{}", self.node_id, sequence),
+            TransactionKind::Registration,
+            self.to_json(),
+            &ValidationPolicy::default(),
+        )?
+        .with_issuer(self.node_id)
+        .with_sequence(sequence);
+        transaction.mine_and_sign(difficulty, signing_key)?;
+        Ok(transaction)
+    }
+
+    pub fn to_node(&self) -> Node {
+        let mut node = Node::new(self.node_id.clone(), self.verifying_key);
+        node.metadata = self.metadata.clone();
+        node.metadata_signature = self.metadata_signature;
+        node
+    }
+}
+
+fn decode_signature(signature: &str) -> Result<Signature, String> {
+    Signature::from_hex(signature).map_err(|_| "registrationInvalidSignature".to_string())
+}
+
+/// Payload of a [`TransactionKind::KeyRotation`] transaction, issued by `node_id` and signed
+/// with the key being replaced. The new key takes over from the transaction's timestamp.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyRotation {
+    pub node_id: String,
+    pub new_key: PublicKey,
+}
+
+#[derive(Serialize, Deserialize)]
+struct KeyRotationPayload {
+    node_id: String,
+    new_key: String,
+}
+
+impl KeyRotation {
+    pub fn new(node_id: impl Into<String>, new_key: impl Into<PublicKey>) -> Self {
+        Self {
+            node_id: node_id.into(),
+            new_key: new_key.into(),
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        let payload = KeyRotationPayload {
+            node_id: self.node_id.clone(),
+            new_key: self.new_key.to_hex(),
+        };
+        serde_json::to_string(&payload).expect("key rotation serializes to JSON")
+    }
+
+    pub fn from_json(payload: &[u8]) -> Result<Self, String> {
+        let payload: KeyRotationPayload = serde_json::from_slice(payload)
+            .map_err(|error| format!("keyRotationInvalidPayload: {}", error))?;
+        Ok(Self {
+            node_id: payload.node_id,
+            new_key: PublicKey::from_hex(&payload.new_key)?,
+        })
+    }
+
+    pub fn into_transaction<S: Signer + ?Sized>(
+        self,
+        current_key: &S,
+        sequence: u64,
+        difficulty: u32,
+    ) -> Result<Transaction, String> {
+        let mut transaction = Transaction::new_with_kind(
+            format!("rotate-{}-{}", self.node_id, sequence),
+            TransactionKind::KeyRotation,
+            self.to_json(),
+            &ValidationPolicy::default(),
+        )?
+        .with_issuer(self.node_id)
+        .with_sequence(sequence);
+        transaction.mine_and_sign(difficulty, current_key)?;
+        Ok(transaction)
+    }
+}
+
+/// Payload of a [`TransactionKind::Revocation`] transaction, issued and signed by an
+/// authority. Transactions signed with `key` at or after `compromised_at` are rejected, and
+/// the authority may install a replacement key from that point.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Revocation {
+    pub node_id: String,
+    pub key: PublicKey,
+    pub compromised_at: u64,
+    pub replacement: Option<PublicKey>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RevocationPayload {
+    node_id: String,
+    key: String,
+    compromised_at: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    replacement: Option<String>,
+}
+
+impl Revocation {
+    pub fn new(node_id: impl Into<String>, key: impl Into<PublicKey>, compromised_at: u64) -> Self {
+        Self {
+            node_id: node_id.into(),
+            key: key.into(),
+            compromised_at,
+            replacement: None,
+        }
+    }
+
+    pub fn with_replacement(mut self, replacement: impl Into<PublicKey>) -> Self {
+        self.replacement = Some(replacement.into());
+        self
+    }
+
+    pub fn to_json(&self) -> String {
+        let payload = RevocationPayload {
+            node_id: self.node_id.clone(),
+            key: self.key.to_hex(),
+            compromised_at: self.compromised_at,
+            replacement: self.replacement.map(|key| key.to_hex()),
+        };
+        serde_json::to_string(&payload).expect("revocation serializes to JSON")
+    }
+
+    pub fn from_json(payload: &[u8]) -> Result<Self, String> {
+        let payload: RevocationPayload = serde_json::from_slice(payload)
+            .map_err(|error| format!("revocationInvalidPayload: {}", error))?;
+        Ok(Self {
+            node_id: payload.node_id,
+            key: PublicKey::from_hex(&payload.key)?,
+            compromised_at: payload.compromised_at,
+            replacement: payload
+                .replacement
+                .as_deref()
+                .map(PublicKey::from_hex)
+                .transpose()?,
+        })
+    }
+
+    pub fn into_transaction<S: Signer + ?Sized>(
+        self,
+        authority: impl Into<String>,
+        authority_key: &S,
+        sequence: u64,
+        difficulty: u32,
+    ) -> Result<Transaction, String> {
+        let mut transaction = Transaction::new_with_kind(
+            format!("revoke-{}-{}", self.node_id, sequence),
+            TransactionKind::Revocation,
+            self.to_json(),
+            &ValidationPolicy::default(),
+        )?
+        .with_issuer(authority)
+        .with_sequence(sequence);
+        transaction.mine_and_sign(difficulty, authority_key)?;
+        Ok(transaction)
+    }
+}
@@ -1,17 +1,143 @@
-use ed25519_dalek::VerifyingKey;
 use rand::Rng;
 
-use crate::{node::Node, Transaction};
+use crate::batch;
+use crate::clock::{Clock, SystemClock};
+use crate::crypto::{PublicKey, SecretKey, Signature, Signer};
+use crate::node::{BoundingBox, Node, StationMetadata, ThresholdPolicy};
+use crate::rate_limit::{RateLimitError, RateLimiter};
+use crate::registry::{KeyRotation, NodeRegistration, Revocation};
+use crate::transaction::{Transaction, TransactionKind};
+use crate::validation::climate::{normalize_climate_payload, ClimateReading, CLIMATE_SCHEMA};
+use crate::validation::{
+    AnomalyDetector, ClimateValidator, PayloadSchema, PayloadValidator, Severity, ValidationPolicy,
+    Violation,
+};
 use futures::stream::{FuturesUnordered, StreamExt};
+use std::collections::BTreeSet;
 use std::collections::HashMap;
 use std::collections::HashSet;
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::Arc;
 use std::time::Duration;
+use tokio::sync::broadcast;
 use tokio::time::sleep;
 
+const DEFAULT_SEQUENCE_WINDOW: u64 = 64;
+const TOPOLOGY_EVENT_CAPACITY: usize = 256;
+
+/// Changes to the node graph, delivered to [`Tangle::subscribe`] receivers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TopologyEvent {
+    NodeAdded(String),
+    NodeRemoved(String),
+    Connected(String, String),
+    Disconnected(String, String),
+}
+
 #[derive(Debug)]
 pub struct Tangle {
     pub nodes: HashMap<String, Node>,
     pub transactions: HashMap<String, Transaction>,
+    pub conflicts: HashMap<(String, u64), Vec<String>>,
+    pub sequences: HashMap<String, SequenceWindow>,
+    pub sequence_window: u64,
+    pub policy: ValidationPolicy,
+    pub clock: Arc<dyn Clock>,
+    pub validators: HashMap<String, Box<dyn PayloadValidator>>,
+    pub anomaly_detector: Option<AnomalyDetector>,
+    pub anomalies: HashMap<String, Vec<Violation>>,
+    /// Latest climate readings per issuer, oldest first, as `(timestamp, transaction id,
+    /// reading)`. Only kept while an anomaly detector is set, and bounded by its `history`.
+    pub recent_readings: HashMap<String, VecDeque<(u64, String, ClimateReading)>>,
+    /// Keys allowed to endorse node registrations.
+    pub authorities: HashMap<String, PublicKey>,
+    /// When set, registration transactions without a valid authority endorsement are rejected.
+    pub require_endorsement: bool,
+    /// Id of the registration transaction each node joined the tangle with.
+    pub registrations: HashMap<String, String>,
+    pub events: broadcast::Sender<TopologyEvent>,
+    pub rate_limiter: RateLimiter,
+}
+
+/// Sequence numbers accepted from one issuer. Transactions may arrive out of order through
+/// propagation, so anything within `window` of the highest sequence is accepted once.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SequenceWindow {
+    pub highest: u64,
+    pub seen: BTreeSet<u64>,
+}
+
+impl SequenceWindow {
+    pub fn check(&self, sequence: u64, window: u64) -> Result<(), String> {
+        if self.seen.contains(&sequence) {
+            return Err(format!("transactionReplay: sequence {}", sequence));
+        }
+
+        if sequence.saturating_add(window) <= self.highest {
+            return Err(format!(
+                "transactionSequenceTooOld: {} (highest: {}, window: {})",
+                sequence, self.highest, window
+            ));
+        }
+
+        if sequence > self.highest.saturating_add(window) {
+            return Err(format!(
+                "transactionSequenceGap: {} (highest: {}, window: {})",
+                sequence, self.highest, window
+            ));
+        }
+
+        Ok(())
+    }
+
+    pub fn record(&mut self, sequence: u64, window: u64) {
+        self.seen.insert(sequence);
+        self.highest = self.highest.max(sequence);
+
+        let oldest = self.highest.saturating_sub(window);
+        self.seen = self.seen.split_off(&oldest);
+    }
+}
+
+/// Why a transaction was not inserted. Rate limiting is kept apart so callers can back off
+/// rather than drop the transaction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TransactionError {
+    RateLimited(RateLimitError),
+    Rejected(String),
+}
+
+impl fmt::Display for TransactionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TransactionError::RateLimited(error) => error.fmt(f),
+            TransactionError::Rejected(reason) => f.write_str(reason),
+        }
+    }
+}
+
+impl std::error::Error for TransactionError {}
+
+impl From<String> for TransactionError {
+    fn from(reason: String) -> Self {
+        TransactionError::Rejected(reason)
+    }
+}
+
+impl From<RateLimitError> for TransactionError {
+    fn from(error: RateLimitError) -> Self {
+        TransactionError::RateLimited(error)
+    }
+}
+
+/// Node registry change carried by a control transaction, applied once the transaction is
+/// accepted.
+#[derive(Debug)]
+enum ControlAction {
+    Registration(NodeRegistration),
+    KeyRotation(KeyRotation),
+    Revocation(Revocation),
 }
 
 impl Default for Tangle {
@@ -22,9 +148,39 @@ impl Default for Tangle {
 
 impl Tangle {
     pub fn new() -> Self {
-        Self {
+        let mut tangle = Self {
             nodes: HashMap::new(),
             transactions: HashMap::new(),
+            conflicts: HashMap::new(),
+            sequences: HashMap::new(),
+            sequence_window: DEFAULT_SEQUENCE_WINDOW,
+            policy: ValidationPolicy::default(),
+            clock: Arc::new(SystemClock),
+            validators: HashMap::new(),
+            anomaly_detector: None,
+            anomalies: HashMap::new(),
+            recent_readings: HashMap::new(),
+            authorities: HashMap::new(),
+            require_endorsement: false,
+            registrations: HashMap::new(),
+            events: broadcast::channel(TOPOLOGY_EVENT_CAPACITY).0,
+            rate_limiter: RateLimiter::new(),
+        };
+        tangle.register_validator(CLIMATE_SCHEMA, ClimateValidator::default());
+        tangle
+    }
+
+    pub fn with_policy(policy: ValidationPolicy) -> Self {
+        Self {
+            policy,
+            ..Self::new()
+        }
+    }
+
+    pub fn with_clock(clock: Arc<dyn Clock>) -> Self {
+        Self {
+            clock,
+            ..Self::new()
         }
     }
 
@@ -32,15 +188,20 @@ impl Tangle {
         let mut current_id = start_id.to_string();
         let mut rng = rand::thread_rng();
         let mut visited = HashSet::new();
+        let mut excluded = HashSet::new();
 
         while let Some(_transaction) = self.transactions.get(&current_id) {
             visited.insert(current_id.clone());
+            // Once one side of a conflict is on the path, the other sides must not be approved.
+            excluded.extend(self.get_conflicts(&current_id));
             let mut neighbors = vec![];
 
             for neighbor_id in self.get_neighbors(&current_id) {
-                if !visited.contains(&neighbor_id) {
+                if !visited.contains(&neighbor_id) && !excluded.contains(&neighbor_id) {
                     if let Some(neighbor) = self.transactions.get(&neighbor_id) {
-                        neighbors.push((neighbor_id.clone(), neighbor.weight));
+                        if !neighbor.rejected {
+                            neighbors.push((neighbor_id.clone(), neighbor.weight));
+                        }
                     }
                 }
             }
@@ -71,52 +232,761 @@ impl Tangle {
             .map_or(vec![], |node| node.neighbors.clone())
     }
 
-    pub fn add_node(&mut self, id: impl Into<String>, verifying_key: VerifyingKey) -> bool {
+    /// Trusted local registration, not recorded in the tangle. Nodes that should be known to
+    /// every participant register through a [`TransactionKind::Registration`] transaction.
+    pub fn add_node(&mut self, id: impl Into<String>, verifying_key: impl Into<PublicKey>) -> bool {
         let id = id.into();
         if self.nodes.contains_key(&id) {
             return false;
         }
-        self.nodes.insert(id.clone(), Node::new(id, verifying_key));
+        self.nodes
+            .insert(id.clone(), Node::new(id.clone(), verifying_key));
+        self.emit(TopologyEvent::NodeAdded(id));
         true
     }
 
-    pub fn connect_nodes(&mut self, id1: &str, id2: &str) -> bool {
-        if let (Some(mut node1), Some(mut node2)) = (self.nodes.remove(id1), self.nodes.remove(id2))
+    /// Drops a node and every edge to it. Transactions it issued stay in the tangle, but new
+    /// ones are refused until it is added or registered again. Its sequence window is kept, so
+    /// a node that returns continues from its old counter and its earlier transactions cannot
+    /// be replayed.
+    pub fn remove_node(&mut self, id: &str) -> bool {
+        let Some(node) = self.nodes.remove(id) else {
+            return false;
+        };
+        for neighbor_id in &node.neighbors {
+            if let Some(neighbor) = self.nodes.get_mut(neighbor_id) {
+                neighbor.remove_neighbor(id);
+            }
+            self.emit(TopologyEvent::Disconnected(
+                id.to_string(),
+                neighbor_id.clone(),
+            ));
+        }
+        self.rate_limiter.forget(id);
+        self.registrations.remove(id);
+        self.emit(TopologyEvent::NodeRemoved(id.to_string()));
+        true
+    }
+
+    pub fn disconnect_nodes(&mut self, id1: &str, id2: &str) -> bool {
+        if !self
+            .nodes
+            .get(id1)
+            .is_some_and(|node| node.is_neighbor(id2))
         {
-            node1.add_neighbor(id2);
-            node2.add_neighbor(id1);
-            self.nodes.insert(id1.to_string(), node1);
-            self.nodes.insert(id2.to_string(), node2);
-            true
-        } else {
-            false
+            return false;
+        }
+        for (id, neighbor_id) in [(id1, id2), (id2, id1)] {
+            if let Some(node) = self.nodes.get_mut(id) {
+                node.remove_neighbor(neighbor_id);
+            }
+        }
+        self.emit(TopologyEvent::Disconnected(
+            id1.to_string(),
+            id2.to_string(),
+        ));
+        true
+    }
+
+    /// Receives topology changes made after this call.
+    pub fn subscribe(&self) -> broadcast::Receiver<TopologyEvent> {
+        self.events.subscribe()
+    }
+
+    fn emit(&self, event: TopologyEvent) {
+        // Sending only fails when nobody is subscribed.
+        let _ = self.events.send(event);
+    }
+
+    /// Attaches station metadata to a known node. The signature must come from
+    /// the node's own key, and that key must not have been revoked.
+    pub fn set_node_metadata(
+        &mut self,
+        node_id: &str,
+        metadata: StationMetadata,
+        signature: Signature,
+    ) -> Result<(), String> {
+        let node = self
+            .nodes
+            .get_mut(node_id)
+            .ok_or_else(|| format!("nodeUnknown: {}", node_id))?;
+        node.set_metadata(metadata, signature, self.clock.now_millis())
+    }
+
+    pub fn get_node(&self, node_id: &str) -> Option<&Node> {
+        self.nodes.get(node_id)
+    }
+
+    /// Nodes whose station location falls inside `bounds`, sorted by id.
+    pub fn nodes_within(&self, bounds: &BoundingBox) -> Vec<&Node> {
+        let mut nodes: Vec<&Node> = self
+            .nodes
+            .values()
+            .filter(|node| {
+                node.location()
+                    .is_some_and(|(latitude, longitude)| bounds.contains(latitude, longitude))
+            })
+            .collect();
+        nodes.sort_by(|a, b| a.id.cmp(&b.id));
+        nodes
+    }
+
+    pub fn connect_nodes(&mut self, id1: &str, id2: &str) -> bool {
+        if id1 == id2 || !self.nodes.contains_key(id1) || !self.nodes.contains_key(id2) {
+            return false;
+        }
+        let already_connected = self.nodes[id1].is_neighbor(id2);
+        for (id, neighbor_id) in [(id1, id2), (id2, id1)] {
+            if let Some(node) = self.nodes.get_mut(id) {
+                node.add_neighbor(neighbor_id);
+            }
+        }
+        if !already_connected {
+            self.emit(TopologyEvent::Connected(id1.to_string(), id2.to_string()));
         }
+        true
+    }
+
+    pub fn register_validator(
+        &mut self,
+        schema: impl Into<String>,
+        validator: impl PayloadValidator + 'static,
+    ) {
+        self.validators.insert(schema.into(), Box::new(validator));
+    }
+
+    pub fn register_schema(&mut self, schema: PayloadSchema) {
+        self.register_validator(schema.name.clone(), schema);
+    }
+
+    /// Requires cosignatures on the node's transactions; `None` lifts the requirement.
+    pub fn set_threshold_policy(
+        &mut self,
+        node_id: &str,
+        policy: Option<ThresholdPolicy>,
+    ) -> Result<(), String> {
+        if let Some(policy) = &policy {
+            policy.validate()?;
+        }
+        let node = self
+            .nodes
+            .get_mut(node_id)
+            .ok_or_else(|| format!("nodeUnknown: {}", node_id))?;
+        node.threshold = policy;
+        Ok(())
     }
 
-    pub fn get_verifying_key(&self, node_id: &str) -> Option<&VerifyingKey> {
-        self.nodes.get(node_id).map(|node| &node.verifying_key)
+    pub fn add_authority(&mut self, name: impl Into<String>, verifying_key: impl Into<PublicKey>) {
+        self.authorities.insert(name.into(), verifying_key.into());
+    }
+
+    /// The node's current key; `None` once it has been revoked without a replacement.
+    pub fn get_verifying_key(&self, node_id: &str) -> Option<&PublicKey> {
+        self.nodes.get(node_id).and_then(Node::current_key)
     }
 
     pub fn add_transaction(&mut self, transaction: Transaction) -> bool {
+        self.try_add_transaction(transaction).is_ok()
+    }
+
+    pub fn try_add_transaction(
+        &mut self,
+        transaction: Transaction,
+    ) -> Result<(), TransactionError> {
+        self.insert_transaction(transaction, None)
+    }
+
+    /// Entry point for transactions gossiped by `neighbor_id`. The neighbor's rate limit is
+    /// checked before any verification work is spent on the transaction.
+    pub fn receive_transaction(
+        &mut self,
+        neighbor_id: &str,
+        transaction: Transaction,
+    ) -> Result<(), TransactionError> {
+        self.rate_limiter
+            .check_neighbor(neighbor_id, self.clock.now_millis())?;
+        self.insert_transaction(transaction, None)
+    }
+
+    /// Inserts many transactions at once, e.g. when a gateway syncs. Stateless checks and
+    /// signatures are verified up front (in parallel and batched with the `batch` feature);
+    /// insertion then runs in order, so a registration may precede its node's readings. Returns
+    /// one result per transaction, in input order.
+    pub fn add_batch(
+        &mut self,
+        transactions: Vec<Transaction>,
+    ) -> Vec<Result<(), TransactionError>> {
+        let valid = batch::validate_all(&transactions, &self.policy, self.clock.as_ref());
+
+        // Keys of nodes registered within the batch are unknown yet; those transactions
+        // are verified individually on insertion.
+        let candidates: Vec<(usize, (&Transaction, PublicKey))> = transactions
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| valid[*index])
+            .filter_map(|(index, transaction)| {
+                let key = match transaction.kind {
+                    TransactionKind::Registration => None,
+                    TransactionKind::Revocation => {
+                        self.authorities.get(&transaction.issuer).copied()
+                    }
+                    _ => self.issuer_key(transaction).ok(),
+                }?;
+                Some((index, (transaction, key)))
+            })
+            .collect();
+        let items: Vec<_> = candidates.iter().map(|(_, item)| *item).collect();
+        let verified = batch::verify_signatures(&items);
+
+        let mut prechecked = vec![None; transactions.len()];
+        for ((index, (_, key)), verified) in candidates.iter().zip(verified) {
+            if verified {
+                prechecked[*index] = Some(*key);
+            }
+        }
+
+        transactions
+            .into_iter()
+            .zip(prechecked)
+            .map(|(transaction, prechecked)| self.insert_transaction(transaction, prechecked))
+            .collect()
+    }
+
+    /// `prechecked` is the key the signature was already verified against, along with the
+    /// stateless checks.
+    fn insert_transaction(
+        &mut self,
+        mut transaction: Transaction,
+        prechecked: Option<PublicKey>,
+    ) -> Result<(), TransactionError> {
         if self.transactions.contains_key(&transaction.id) {
-            return false;
+            return Err(format!("transactionDuplicate: {}", transaction.id).into());
         }
 
-        if let Err(_error) = transaction.validate() {
-            return false;
+        if prechecked.is_none() {
+            transaction.validate_with(&self.policy, self.clock.as_ref())?;
         }
 
-        if let Some(verifying_key) = self.get_verifying_key(&transaction.id) {
-            if let Err(_error) = transaction.validate_signature(verifying_key) {
-                return false;
+        // A registration is self-signed by the key it announces and a revocation is signed by
+        // an authority; everything else is checked against the issuer's key at its timestamp.
+        let (verifying_key, control) = match transaction.kind {
+            TransactionKind::Data => (self.issuer_key(&transaction)?, None),
+            TransactionKind::Registration => {
+                let registration = self.check_registration(&transaction)?;
+                (
+                    registration.verifying_key,
+                    Some(ControlAction::Registration(registration)),
+                )
+            }
+            TransactionKind::KeyRotation => {
+                let rotation = self.check_key_rotation(&transaction)?;
+                (
+                    self.issuer_key(&transaction)?,
+                    Some(ControlAction::KeyRotation(rotation)),
+                )
+            }
+            TransactionKind::Revocation => {
+                let authority_key = *self
+                    .authorities
+                    .get(&transaction.issuer)
+                    .ok_or_else(|| format!("revocationUnknownAuthority: {}", transaction.issuer))?;
+                let revocation = self.check_revocation(&transaction)?;
+                (authority_key, Some(ControlAction::Revocation(revocation)))
+            }
+        };
+        // The key may have changed since the precheck if the same batch rotated it.
+        if prechecked != Some(verifying_key) {
+            transaction.validate_signature(&verifying_key)?;
+        }
+
+        if transaction.kind == TransactionKind::Data {
+            self.check_cosignatures(&transaction)?;
+        }
+
+        // A registration replacing an outranked one starts the node's counter afresh: the
+        // sequences used so far belong to whoever registered first.
+        let replaces_registration = transaction.kind == TransactionKind::Registration
+            && self.nodes.contains_key(&transaction.issuer);
+
+        // The very first sequence seen from an issuer is accepted as is, since a node joining
+        // late cannot know where the issuer's counter started.
+        if let Some(window) = self.sequences.get(&transaction.issuer) {
+            if !replaces_registration {
+                window.check(transaction.sequence, self.sequence_window)?;
+            }
+        }
+
+        // Untagged payloads are opaque to the tangle; tagged ones must match a known schema.
+        // Encrypted payloads can only be checked by their recipients, see `decrypt_payload`.
+        if let Some(schema) = &transaction.schema {
+            let validator = self
+                .validators
+                .get(schema)
+                .ok_or_else(|| format!("transactionUnknownSchema: {}", schema))?;
+            if !transaction.encrypted {
+                validator
+                    .validate(&transaction.payload)
+                    .map_err(|error| format!("transactionPayloadInvalid: {}", error))?;
             }
-        } else {
-            return false;
         }
 
+        let reading = self.climate_reading(&transaction);
+        let anomalies = reading.as_ref().map_or_else(Vec::new, |reading| {
+            self.detect_anomalies(&transaction, reading)
+        });
+        if let Some(anomaly) = anomalies
+            .iter()
+            .find(|anomaly| anomaly.severity == Severity::Error)
+        {
+            return Err(format!("transactionAnomalous: {}", anomaly.message()).into());
+        }
+
+        // Charged only once the transaction is certain to be stored: a rejected transaction
+        // can be replayed freely, so charging it would let anyone drain the issuer's bucket.
+        self.rate_limiter
+            .check_issuer(&transaction.issuer, self.clock.now_millis())?;
+
+        if replaces_registration {
+            self.sequences.remove(&transaction.issuer);
+        }
+        self.sequences
+            .entry(transaction.issuer.clone())
+            .or_insert_with(|| SequenceWindow {
+                highest: transaction.sequence,
+                seen: BTreeSet::new(),
+            })
+            .record(transaction.sequence, self.sequence_window);
+
+        if let Some(slot) = transaction.slot {
+            self.conflicts
+                .entry((transaction.issuer.clone(), slot))
+                .or_default()
+                .push(transaction.id.clone());
+        }
+
+        if !anomalies.is_empty() {
+            self.anomalies.insert(transaction.id.clone(), anomalies);
+        }
+
+        if let Some(reading) = reading {
+            self.record_reading(&transaction, reading);
+        }
+
+        if let Some(control) = control {
+            self.apply_control(control, &transaction);
+        }
+
+        // Weight is not covered by the signature, so whatever the sender or a relay claimed is
+        // discarded; nothing approves a transaction yet when it is inserted.
+        transaction.weight = transaction.calculate_weight(0);
+
         self.transactions
             .insert(transaction.id.clone(), transaction);
-        true
+        Ok(())
+    }
+
+    fn issuer_key(&self, transaction: &Transaction) -> Result<PublicKey, String> {
+        let node = self
+            .nodes
+            .get(&transaction.issuer)
+            .ok_or_else(|| format!("transactionUnknownIssuer: {}", transaction.issuer))?;
+        node.key_at(transaction.timestamp).copied().ok_or_else(|| {
+            format!(
+                "transactionKeyRevoked: {} (timestamp: {})",
+                transaction.issuer, transaction.timestamp
+            )
+        })
+    }
+
+    fn check_cosignatures(&self, transaction: &Transaction) -> Result<(), String> {
+        let Some(policy) = self
+            .nodes
+            .get(&transaction.issuer)
+            .and_then(|node| node.threshold.as_ref())
+            .filter(|policy| policy.applies_to(transaction.schema.as_deref()))
+        else {
+            return Ok(());
+        };
+
+        // Policies assigned to `Node::threshold` directly skip validation, so count each
+        // signer once here as well.
+        let signers: BTreeSet<&String> = policy.signers.iter().collect();
+        let mut endorsed = 0;
+        for signer in signers {
+            if !transaction
+                .cosignatures
+                .iter()
+                .any(|cosignature| &cosignature.signer == signer)
+            {
+                continue;
+            }
+            let verifying_key = self
+                .nodes
+                .get(signer)
+                .and_then(|node| node.key_at(transaction.timestamp))
+                .ok_or_else(|| format!("transactionUnknownCosigner: {}", signer))?;
+            transaction.validate_cosignature(signer, verifying_key)?;
+            endorsed += 1;
+        }
+
+        if endorsed < policy.threshold {
+            return Err(format!(
+                "transactionInsufficientCosignatures: {}/{}",
+                endorsed, policy.threshold
+            ));
+        }
+        Ok(())
+    }
+
+    fn check_registration(&self, transaction: &Transaction) -> Result<NodeRegistration, String> {
+        let registration = NodeRegistration::from_json(&transaction.payload)?;
+        if registration.node_id != transaction.issuer {
+            return Err(format!(
+                "registrationIssuerMismatch: {} (issuer: {})",
+                registration.node_id, transaction.issuer
+            ));
+        }
+        if self.nodes.contains_key(&registration.node_id)
+            && !self.outranks_registration(transaction, &registration)
+        {
+            return Err(format!("registrationNodeExists: {}", registration.node_id));
+        }
+        registration.validate()?;
+
+        match &registration.endorsement {
+            Some(endorsement) => {
+                let authority_key =
+                    self.authorities
+                        .get(&endorsement.authority)
+                        .ok_or_else(|| {
+                            format!("registrationUnknownAuthority: {}", endorsement.authority)
+                        })?;
+                registration.verify_endorsement(authority_key)?;
+            }
+            None if self.require_endorsement => {
+                return Err("registrationNotEndorsed".to_string());
+            }
+            None => {}
+        }
+
+        Ok(registration)
+    }
+
+    /// Competing registrations for one node id are settled the same way wherever they arrive
+    /// first: an endorsed registration beats an unendorsed one, then the earliest timestamp
+    /// wins, then the lowest transaction id. Nodes added locally, and nodes that have already
+    /// rotated or revoked a key, keep their registration.
+    fn outranks_registration(
+        &self,
+        transaction: &Transaction,
+        registration: &NodeRegistration,
+    ) -> bool {
+        let Some(existing) = self
+            .registrations
+            .get(&registration.node_id)
+            .and_then(|id| self.transactions.get(id))
+        else {
+            return false;
+        };
+        let settled = self.nodes.get(&registration.node_id).is_some_and(|node| {
+            node.keys.len() > 1 || node.keys.iter().any(|record| record.revoked_at.is_some())
+        });
+        if settled {
+            return false;
+        }
+
+        let existing_endorsed = NodeRegistration::from_json(&existing.payload)
+            .is_ok_and(|existing| existing.endorsement.is_some());
+        let rank = |endorsed: bool, transaction: &Transaction| {
+            (!endorsed, transaction.timestamp, transaction.id.clone())
+        };
+        rank(registration.endorsement.is_some(), transaction) < rank(existing_endorsed, existing)
+    }
+
+    fn check_key_rotation(&self, transaction: &Transaction) -> Result<KeyRotation, String> {
+        let rotation = KeyRotation::from_json(&transaction.payload)?;
+        if rotation.node_id != transaction.issuer {
+            return Err(format!(
+                "keyRotationIssuerMismatch: {} (issuer: {})",
+                rotation.node_id, transaction.issuer
+            ));
+        }
+        let node = self
+            .nodes
+            .get(&rotation.node_id)
+            .ok_or_else(|| format!("transactionUnknownIssuer: {}", rotation.node_id))?;
+        // Whoever holds a revoked key must not be able to rotate away from it, even with a
+        // backdated rotation that predates the revocation.
+        if node.is_revoked(&self.issuer_key(transaction)?) {
+            return Err(format!("keyRotationRevokedKey: {}", rotation.node_id));
+        }
+        node.check_rotation(&rotation.new_key, transaction.timestamp)?;
+        Ok(rotation)
+    }
+
+    fn check_revocation(&self, transaction: &Transaction) -> Result<Revocation, String> {
+        let revocation = Revocation::from_json(&transaction.payload)?;
+        let node = self
+            .nodes
+            .get(&revocation.node_id)
+            .ok_or_else(|| format!("revocationUnknownNode: {}", revocation.node_id))?;
+        if !node
+            .keys
+            .iter()
+            .any(|record| record.verifying_key == revocation.key)
+        {
+            return Err(format!("revocationUnknownKey: {}", revocation.node_id));
+        }
+        if let Some(replacement) = &revocation.replacement {
+            // Rotations the leaked key signed are undone first, so check against the result.
+            let mut revoked = node.clone();
+            revoked.revoke_key(&revocation.key, revocation.compromised_at);
+            revoked.check_rotation(replacement, revocation.compromised_at)?;
+        }
+        Ok(revocation)
+    }
+
+    fn apply_control(&mut self, control: ControlAction, transaction: &Transaction) {
+        let timestamp = transaction.timestamp;
+        match control {
+            ControlAction::Registration(registration) => {
+                let node_id = registration.node_id.clone();
+                let mut node = registration.to_node();
+                // Replacing an outranked registration: the edges stay, everything the previous
+                // owner signed is rejected.
+                let replaced = self.nodes.remove(&node_id);
+                if let Some(replaced) = &replaced {
+                    node.neighbors = replaced.neighbors.clone();
+                }
+                let superseded = self
+                    .registrations
+                    .insert(node_id.clone(), transaction.id.clone());
+                self.nodes.insert(node_id.clone(), node);
+                if replaced.is_some() {
+                    if let Some(superseded) =
+                        superseded.and_then(|id| self.transactions.get_mut(&id))
+                    {
+                        superseded.reject();
+                    }
+                    self.recent_readings.remove(&node_id);
+                    self.reject_invalidated(&node_id);
+                } else {
+                    self.emit(TopologyEvent::NodeAdded(node_id));
+                }
+            }
+            ControlAction::KeyRotation(rotation) => {
+                if let Some(node) = self.nodes.get_mut(&rotation.node_id) {
+                    // Checked in `check_key_rotation`.
+                    let _ = node.rotate_key(rotation.new_key, timestamp);
+                }
+            }
+            ControlAction::Revocation(revocation) => {
+                if let Some(node) = self.nodes.get_mut(&revocation.node_id) {
+                    node.revoke_key(&revocation.key, revocation.compromised_at);
+                    if let Some(replacement) = revocation.replacement {
+                        let _ = node.rotate_key(replacement, revocation.compromised_at);
+                    }
+                }
+                self.reject_invalidated(&revocation.node_id);
+            }
+        }
+    }
+
+    /// Rejects already accepted transactions of `node_id` that no longer verify against the
+    /// key valid at their timestamp, i.e. those signed with a revoked key after compromise or
+    /// with a key a revoked key rotated to.
+    fn reject_invalidated(&mut self, node_id: &str) {
+        let Some(node) = self.nodes.get(node_id) else {
+            return;
+        };
+        for transaction in self.transactions.values_mut() {
+            if transaction.issuer == node_id
+                && !transaction.rejected
+                && node
+                    .key_at(transaction.timestamp)
+                    .is_none_or(|key| transaction.validate_signature(key).is_err())
+            {
+                transaction.reject();
+            }
+        }
+    }
+
+    /// Normalized climate reading of `transaction`, if an anomaly detector would look at it.
+    fn climate_reading(&self, transaction: &Transaction) -> Option<ClimateReading> {
+        self.anomaly_detector.as_ref()?;
+        if transaction.schema.as_deref() != Some(CLIMATE_SCHEMA) || transaction.encrypted {
+            return None;
+        }
+        normalize_climate_payload(&transaction.payload)
+            .ok()
+            .map(|normalized| normalized.reading)
+    }
+
+    fn detect_anomalies(
+        &self,
+        transaction: &Transaction,
+        reading: &ClimateReading,
+    ) -> Vec<Violation> {
+        let Some(detector) = &self.anomaly_detector else {
+            return vec![];
+        };
+        let Some(recent) = self.recent_readings.get(&transaction.issuer) else {
+            return detector.detect(&[], transaction.timestamp, reading);
+        };
+
+        // Readings may have been rejected since, e.g. by conflict resolution or a revocation.
+        let history: Vec<(u64, ClimateReading)> = recent
+            .iter()
+            .filter(|(timestamp, id, _)| {
+                *timestamp < transaction.timestamp
+                    && self
+                        .transactions
+                        .get(id)
+                        .is_some_and(|previous| !previous.rejected)
+            })
+            .map(|(timestamp, _, reading)| (*timestamp, *reading))
+            .collect();
+        let history = &history[history.len().saturating_sub(detector.history)..];
+
+        detector.detect(history, transaction.timestamp, reading)
+    }
+
+    fn record_reading(&mut self, transaction: &Transaction, reading: ClimateReading) {
+        let Some(detector) = &self.anomaly_detector else {
+            return;
+        };
+        let recent = self
+            .recent_readings
+            .entry(transaction.issuer.clone())
+            .or_default();
+        // Readings arrive out of order through propagation; keep them sorted by timestamp.
+        let position =
+            recent.partition_point(|(timestamp, _, _)| *timestamp <= transaction.timestamp);
+        recent.insert(
+            position,
+            (transaction.timestamp, transaction.id.clone(), reading),
+        );
+        while recent.len() > detector.history {
+            recent.pop_front();
+        }
+    }
+
+    /// Decrypts a stored transaction's payload for `recipient_id` and checks the plaintext
+    /// against the transaction's schema, which relaying nodes could not do.
+    pub fn decrypt_payload(
+        &self,
+        transaction_id: &str,
+        recipient_id: &str,
+        secret_key: &SecretKey,
+    ) -> Result<Vec<u8>, String> {
+        let transaction = self
+            .transactions
+            .get(transaction_id)
+            .ok_or_else(|| format!("transactionUnknown: {}", transaction_id))?;
+        let node = self
+            .nodes
+            .get(recipient_id)
+            .ok_or_else(|| format!("nodeUnknown: {}", recipient_id))?;
+        // Older keys still open envelopes sealed before a rotation, revoked ones do not.
+        let public_key = secret_key.public_key();
+        if !node
+            .keys
+            .iter()
+            .any(|record| record.verifying_key == public_key)
+        {
+            return Err(format!("encryptionKeyMismatch: {}", recipient_id));
+        }
+        if node.is_revoked(&public_key) {
+            return Err(format!("encryptionKeyRevoked: {}", recipient_id));
+        }
+
+        let payload = transaction.decrypt_payload(recipient_id, secret_key)?;
+        if let Some(validator) = transaction
+            .schema
+            .as_ref()
+            .and_then(|schema| self.validators.get(schema))
+        {
+            validator
+                .validate(&payload)
+                .map_err(|error| format!("transactionPayloadInvalid: {}", error))?;
+        }
+        Ok(payload)
+    }
+
+    pub fn is_anomalous(&self, transaction_id: &str) -> bool {
+        self.anomalies.contains_key(transaction_id)
+    }
+
+    pub fn get_conflicts(&self, transaction_id: &str) -> Vec<String> {
+        let Some(transaction) = self.transactions.get(transaction_id) else {
+            return vec![];
+        };
+        let Some(slot) = transaction.slot else {
+            return vec![];
+        };
+
+        self.conflicts
+            .get(&(transaction.issuer.clone(), slot))
+            .map_or(vec![], |ids| {
+                ids.iter()
+                    .filter(|id| id.as_str() != transaction_id)
+                    .cloned()
+                    .collect()
+            })
+    }
+
+    pub fn is_conflicting(&self, transaction_id: &str) -> bool {
+        !self.get_conflicts(transaction_id).is_empty()
+    }
+
+    /// Settles each conflicting slot on one winner and rejects the rest, returning the IDs
+    /// rejected by this call. Transactions do not record approvals yet, so there is no
+    /// cumulative weight to compare: the earliest timestamp wins, then the lowest ID. The
+    /// winner is confirmed and keeps the slot, so a transaction arriving later cannot take it
+    /// over by claiming an earlier timestamp.
+    pub fn resolve_conflicts(&mut self) -> Vec<String> {
+        let mut rejected = Vec::new();
+        let mut confirmed = Vec::new();
+
+        for ids in self.conflicts.values() {
+            let mut candidates: Vec<&Transaction> = ids
+                .iter()
+                .filter_map(|id| self.transactions.get(id))
+                .filter(|transaction| !transaction.rejected)
+                .collect();
+
+            if candidates.len() < 2 {
+                continue;
+            }
+
+            candidates.sort_by(|a, b| {
+                b.confirmed
+                    .cmp(&a.confirmed)
+                    .then(a.timestamp.cmp(&b.timestamp))
+                    .then(a.id.cmp(&b.id))
+            });
+
+            confirmed.push(candidates[0].id.clone());
+            rejected.extend(
+                candidates[1..]
+                    .iter()
+                    .map(|transaction| transaction.id.clone()),
+            );
+        }
+
+        for id in &confirmed {
+            if let Some(transaction) = self.transactions.get_mut(id) {
+                transaction.confirm();
+            }
+        }
+
+        for id in &rejected {
+            if let Some(transaction) = self.transactions.get_mut(id) {
+                transaction.reject();
+            }
+        }
+
+        rejected
     }
 
     pub async fn propagate_transaction(
@@ -162,7 +1032,7 @@ impl Tangle {
     pub fn get_snapshot(&self) -> Vec<(String, Vec<String>)> {
         let mut snapshot = Vec::new();
 
-        for (tx_id, _transaction) in &self.transactions {
+        for tx_id in self.transactions.keys() {
             let neighbors = self.get_neighbors(tx_id);
             snapshot.push((tx_id.clone(), neighbors));
         }
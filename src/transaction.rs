@@ -1,97 +1,273 @@
-use std::time::{SystemTime, UNIX_EPOCH};
-
-use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
 use rand::Rng;
+use sha2::{Digest, Sha256};
+
+use crate::clock::{Clock, SystemClock};
+use crate::crypto::{Algorithm, PublicKey, SecretKey, Signature, Signer, Verifier};
+use crate::encryption::EncryptedPayload;
+use crate::validation::policy::MAX_POW_DIFFICULTY;
+use crate::validation::ValidationPolicy;
 
-const MAX_PAYLOAD_SIZE: usize = 256;
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TransactionKind {
+    /// Sensor data or any other application payload.
+    #[default]
+    Data,
+    /// A node announcing its own key, see [`crate::registry::NodeRegistration`].
+    Registration,
+    /// A node replacing its key, signed with the outgoing key.
+    KeyRotation,
+    /// An authority invalidating a compromised node key.
+    Revocation,
+}
+
+impl TransactionKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TransactionKind::Data => "data",
+            TransactionKind::Registration => "registration",
+            TransactionKind::KeyRotation => "keyRotation",
+            TransactionKind::Revocation => "revocation",
+        }
+    }
+}
+
+/// Additional signature over the same data as the issuer's, e.g. a technician endorsing a
+/// calibration record.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cosignature {
+    pub signer: String,
+    pub signature: Signature,
+}
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Transaction {
     pub id: String,
-    pub payload: String,
+    pub issuer: String,
+    pub kind: TransactionKind,
+    pub slot: Option<u64>,
+    pub sequence: u64,
+    pub schema: Option<String>,
+    /// The payload is an [`EncryptedPayload`] envelope; `schema` describes the plaintext.
+    pub encrypted: bool,
+    pub payload: Vec<u8>,
     pub timestamp: u64,
     nonce: u64,
+    /// Scheme of `signature`, covered by the signature itself.
+    pub algorithm: Algorithm,
     pub signature: Option<Signature>,
+    pub cosignatures: Vec<Cosignature>,
     pub weight: u32,
     pub confirmed: bool,
-}
-
-fn is_valid_id(id: &str) -> bool {
-    id.chars().all(|c| c.is_alphanumeric() || c == '-')
+    pub rejected: bool,
 }
 
 impl Transaction {
-    pub fn new(id: impl Into<String>, payload: impl Into<String>) -> Result<Self, String> {
+    pub fn new(id: impl Into<String>, payload: impl Into<Vec<u8>>) -> Result<Self, String> {
+        Self::new_with_policy(id, payload, &ValidationPolicy::default())
+    }
+
+    pub fn new_with_policy(
+        id: impl Into<String>,
+        payload: impl Into<Vec<u8>>,
+        policy: &ValidationPolicy,
+    ) -> Result<Self, String> {
+        Self::new_with_kind(id, TransactionKind::Data, payload, policy)
+    }
+
+    pub fn new_with_kind(
+        id: impl Into<String>,
+        kind: TransactionKind,
+        payload: impl Into<Vec<u8>>,
+        policy: &ValidationPolicy,
+    ) -> Result<Self, String> {
         let id = id.into();
 
         if id.trim().is_empty() {
             return Err("transactionInvalidId".to_string());
         }
 
-        if !is_valid_id(&id) {
+        if !policy.id.is_valid_format(&id) {
             return Err("transactionInvalidIdFormat".to_string());
         }
 
+        if !policy.id.is_valid_length(&id) {
+            return Err("transactionInvalidIdLength".to_string());
+        }
+
         let payload = payload.into();
-        if payload.trim().is_empty() {
+        if payload.trim_ascii().is_empty() {
             return Err("transactionInvalidPayload".to_string());
         }
 
-        if payload.len() > MAX_PAYLOAD_SIZE {
+        if payload.len() > policy.max_payload_size_for(kind) {
             return Err("transactionPayloadTooLarge".to_string());
         }
 
-        let timestamp = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .expect("Time went backwards")
-            .as_millis() as u64;
+        let timestamp = SystemClock.now_millis();
 
         let nonce = rand::thread_rng().gen::<u64>();
 
         Ok(Self {
+            issuer: id.clone(),
             id,
+            kind,
+            slot: None,
+            sequence: 0,
+            schema: None,
+            encrypted: false,
             payload,
             timestamp,
             nonce,
+            algorithm: Algorithm::default(),
             signature: None,
+            cosignatures: Vec::new(),
             weight: 0,
             confirmed: false,
+            rejected: false,
         })
     }
 
+    pub fn with_issuer(mut self, issuer: impl Into<String>) -> Self {
+        self.issuer = issuer.into();
+        self
+    }
+
+    pub fn with_slot(mut self, slot: u64) -> Self {
+        self.slot = Some(slot);
+        self
+    }
+
+    pub fn with_sequence(mut self, sequence: u64) -> Self {
+        self.sequence = sequence;
+        self
+    }
+
+    pub fn with_schema(mut self, schema: impl Into<String>) -> Self {
+        self.schema = Some(schema.into());
+        self
+    }
+
+    /// Replaces the payload with an envelope only `recipients` can open. Call before signing.
+    /// Recipients must have ed25519 keys; see [`crate::encryption`].
+    pub fn encrypt_for(mut self, recipients: &[(&str, &PublicKey)]) -> Result<Self, String> {
+        if self.encrypted {
+            return Err("transactionAlreadyEncrypted".to_string());
+        }
+        self.payload = EncryptedPayload::encrypt(&self.payload, recipients)?.to_bytes();
+        self.encrypted = true;
+        Ok(self)
+    }
+
+    pub fn decrypt_payload(
+        &self,
+        recipient: &str,
+        secret_key: &SecretKey,
+    ) -> Result<Vec<u8>, String> {
+        if !self.encrypted {
+            return Err("transactionNotEncrypted".to_string());
+        }
+        EncryptedPayload::from_bytes(&self.payload)?.decrypt(recipient, secret_key)
+    }
+
     pub fn validate(&self) -> Result<(), String> {
+        self.validate_with(&ValidationPolicy::default(), &SystemClock)
+    }
+
+    pub fn validate_with(
+        &self,
+        policy: &ValidationPolicy,
+        clock: &dyn Clock,
+    ) -> Result<(), String> {
         if self.id.trim().is_empty() {
             return Err("transactionInvalidId: ID is empty".into());
         }
 
-        if !is_valid_id(&self.id) {
+        if !policy.id.is_valid_format(&self.id) {
             return Err(format!("transactionInvalidIdFormat: {}", self.id));
         }
 
-        if self.payload.trim().is_empty() {
+        if !policy.id.is_valid_length(&self.id) {
+            return Err(format!("transactionInvalidIdLength: {}", self.id));
+        }
+
+        if self.issuer.trim().is_empty()
+            || !policy.id.is_valid_format(&self.issuer)
+            || !policy.id.is_valid_length(&self.issuer)
+        {
+            return Err(format!("transactionInvalidIssuer: {}", self.issuer));
+        }
+
+        if self.payload.trim_ascii().is_empty() {
             return Err("transactionInvalidPayload: Payload is empty".into());
         }
 
-        if self.payload.len() > MAX_PAYLOAD_SIZE {
+        let max_payload_size = policy.max_payload_size_for(self.kind);
+        if self.payload.len() > max_payload_size {
             return Err(format!(
                 "transactionPayloadTooLarge: {} bytes (max: {} bytes)",
                 self.payload.len(),
-                MAX_PAYLOAD_SIZE
+                max_payload_size
             ));
         }
 
-        let now = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .expect("Time went backwards")
-            .as_millis() as u64;
+        if self.encrypted {
+            EncryptedPayload::from_bytes(&self.payload)
+                .map_err(|error| format!("transactionPayloadInvalid: {}", error))?;
+        } else {
+            policy.check_required_fields(self.kind, &self.payload)?;
+        }
 
-        if self.timestamp > now {
-            return Err(format!(
-                "transactionTimestampInvalid: {} (now: {})",
-                self.timestamp, now
-            ));
+        check_pow_difficulty(policy.pow_difficulty)?;
+        if policy.pow_difficulty > 0 {
+            let work = self.work();
+            if work < policy.pow_difficulty {
+                return Err(format!(
+                    "transactionInsufficientWork: {} (required: {})",
+                    work, policy.pow_difficulty
+                ));
+            }
         }
 
+        policy.timestamps.check(self.timestamp, clock.now_millis())
+    }
+
+    pub fn nonce(&self) -> u64 {
+        self.nonce
+    }
+
+    /// Leading zero bits of the SHA-256 hash of the signed data.
+    pub fn work(&self) -> u32 {
+        let hash = Sha256::digest(self.serialize());
+        let mut bits = 0;
+        for byte in hash {
+            bits += byte.leading_zeros();
+            if byte != 0 {
+                break;
+            }
+        }
+        bits
+    }
+
+    /// Searches for a nonce giving at least `difficulty` bits of work. The nonce and algorithm
+    /// are signed, so mine before signing or cosigning, with `algorithm` already set to the
+    /// signer's; [`Transaction::mine_and_sign`] takes care of both. Each extra bit doubles the
+    /// expected effort.
+    pub fn mine(&mut self, difficulty: u32) -> Result<(), String> {
+        check_pow_difficulty(difficulty)?;
+        while self.work() < difficulty {
+            self.nonce = self.nonce.wrapping_add(1);
+        }
+        Ok(())
+    }
+
+    pub fn mine_and_sign<S: Signer + ?Sized>(
+        &mut self,
+        difficulty: u32,
+        signing_key: &S,
+    ) -> Result<(), String> {
+        self.algorithm = signing_key.algorithm();
+        self.mine(difficulty)?;
+        self.sign(signing_key);
         Ok(())
     }
 
@@ -99,30 +275,111 @@ impl Transaction {
         (approvals as u32).max(1)
     }
 
-    fn serialize(&self) -> String {
-        format!(
-            "{}:{}:{}:{}",
-            self.id, self.payload, self.timestamp, self.nonce
+    pub fn payload_str(&self) -> Option<&str> {
+        std::str::from_utf8(&self.payload).ok()
+    }
+
+    pub(crate) fn serialize(&self) -> Vec<u8> {
+        let slot = self.slot.map_or_else(String::new, |slot| slot.to_string());
+        let mut data = format!(
+            "{}:{}:{}:{}:{}:",
+            self.kind.as_str(),
+            self.algorithm.as_str(),
+            self.sequence,
+            slot,
+            self.encrypted
         )
+        .into_bytes();
+        // Free-form fields are length-prefixed rather than delimited, so a ':' in an id or
+        // schema name (or anywhere in a binary payload) cannot move bytes between fields.
+        for field in [
+            self.id.as_bytes(),
+            self.issuer.as_bytes(),
+            self.schema.as_deref().unwrap_or_default().as_bytes(),
+            &self.payload,
+        ] {
+            data.extend_from_slice(format!("{}:", field.len()).as_bytes());
+            data.extend_from_slice(field);
+            data.push(b':');
+        }
+        data.extend_from_slice(format!("{}:{}", self.timestamp, self.nonce).as_bytes());
+        data
     }
 
-    pub fn sign(&mut self, signing_key: &SigningKey) {
+    pub fn sign<S: Signer + ?Sized>(&mut self, signing_key: &S) {
+        self.algorithm = signing_key.algorithm();
         let data = self.serialize();
-        self.signature = Some(signing_key.sign(data.as_bytes()));
+        self.signature = Some(signing_key.sign_message(&data));
     }
 
-    pub fn validate_signature(&self, verifying_key: &VerifyingKey) -> Result<(), String> {
+    pub fn validate_signature<V: Verifier + ?Sized>(
+        &self,
+        verifying_key: &V,
+    ) -> Result<(), String> {
         if let Some(signature) = &self.signature {
+            if verifying_key.algorithm() != self.algorithm {
+                return Err(format!(
+                    "signatureAlgorithmMismatch: {} (key: {})",
+                    self.algorithm.as_str(),
+                    verifying_key.algorithm().as_str()
+                ));
+            }
             let data = self.serialize();
             verifying_key
-                .verify(data.as_bytes(), signature)
+                .verify_message(&data, signature)
                 .map_err(|_| "Invalid signature".to_string())
         } else {
             Err("Transaction is not signed".to_string())
         }
     }
 
+    /// Adds `signer`'s signature, replacing any earlier one from the same signer. Cosignatures
+    /// cover the issuer's algorithm, so sign first.
+    pub fn cosign<S: Signer + ?Sized>(&mut self, signer: impl Into<String>, signing_key: &S) {
+        let signer = signer.into();
+        let signature = signing_key.sign_message(&self.serialize());
+        self.cosignatures
+            .retain(|cosignature| cosignature.signer != signer);
+        self.cosignatures.push(Cosignature { signer, signature });
+    }
+
+    pub fn validate_cosignature<V: Verifier + ?Sized>(
+        &self,
+        signer: &str,
+        verifying_key: &V,
+    ) -> Result<(), String> {
+        let cosignature = self
+            .cosignatures
+            .iter()
+            .find(|cosignature| cosignature.signer == signer)
+            .ok_or_else(|| format!("transactionMissingCosignature: {}", signer))?;
+        verifying_key
+            .verify_message(&self.serialize(), &cosignature.signature)
+            .map_err(|_| format!("transactionInvalidCosignature: {}", signer))
+    }
+
     pub fn confirm(&mut self) {
         self.confirmed = true;
     }
+
+    pub fn reject(&mut self) {
+        self.rejected = true;
+    }
+
+    pub fn conflicts_with(&self, other: &Transaction) -> bool {
+        self.id != other.id
+            && self.issuer == other.issuer
+            && self.slot.is_some()
+            && self.slot == other.slot
+    }
+}
+
+fn check_pow_difficulty(difficulty: u32) -> Result<(), String> {
+    if difficulty > MAX_POW_DIFFICULTY {
+        return Err(format!(
+            "powDifficultyTooHigh: {} (max: {})",
+            difficulty, MAX_POW_DIFFICULTY
+        ));
+    }
+    Ok(())
 }
@@ -0,0 +1,104 @@
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use super::climate::{ClimateReading, CLIMATE_FIELDS};
+use super::{Severity, Violation};
+
+/// Flags climate readings that are individually valid but implausible given the issuer's
+/// previous readings.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnomalyDetector {
+    /// Largest plausible change per minute, keyed by field name.
+    pub max_rates: HashMap<String, f64>,
+    /// Number of identical consecutive values after which a field is reported as stuck.
+    pub stuck_after: Option<usize>,
+    /// How many previous readings of the issuer are taken into account.
+    pub history: usize,
+    /// Reject anomalous transactions instead of only flagging them.
+    pub reject: bool,
+}
+
+impl Default for AnomalyDetector {
+    fn default() -> Self {
+        let max_rates = [
+            ("temperature", 5.0),
+            ("humidity", 20.0),
+            ("pressure", 5.0),
+            ("dew_point", 5.0),
+        ]
+        .into_iter()
+        .map(|(field, rate)| (field.to_string(), rate))
+        .collect();
+
+        Self {
+            max_rates,
+            stuck_after: Some(10),
+            history: 16,
+            reject: false,
+        }
+    }
+}
+
+impl AnomalyDetector {
+    /// `history` holds `(timestamp, reading)` pairs older than `timestamp`, oldest first.
+    pub fn detect(
+        &self,
+        history: &[(u64, ClimateReading)],
+        timestamp: u64,
+        reading: &ClimateReading,
+    ) -> Vec<Violation> {
+        let severity = if self.reject {
+            Severity::Error
+        } else {
+            Severity::Warning
+        };
+        let mut anomalies = Vec::new();
+
+        for field in &CLIMATE_FIELDS {
+            let Some(value) = reading.get(field.name) else {
+                continue;
+            };
+            let previous: Vec<(u64, f64)> = history
+                .iter()
+                .filter_map(|(at, past)| past.get(field.name).map(|past| (*at, past)))
+                .collect();
+
+            if let (Some(max_rate), Some((previous_at, previous_value))) =
+                (self.max_rates.get(field.name), previous.last())
+            {
+                let minutes = timestamp.saturating_sub(*previous_at).max(1) as f64 / 60_000.0;
+                let max_delta = max_rate * minutes;
+                if (value - previous_value).abs() > max_delta {
+                    anomalies.push(Violation {
+                        field: field.name.to_string(),
+                        code: format!("{}RateExceeded", field.label),
+                        value: Value::from(value),
+                        allowed: Some((previous_value - max_delta, previous_value + max_delta)),
+                        severity,
+                    });
+                }
+            }
+
+            if let Some(stuck_after) = self.stuck_after {
+                let repeated = previous
+                    .iter()
+                    .rev()
+                    .take_while(|(_, past)| *past == value)
+                    .count();
+                if stuck_after > 0 && repeated + 1 >= stuck_after {
+                    anomalies.push(Violation {
+                        severity,
+                        ..Violation::error(
+                            field.name,
+                            format!("{}Stuck", field.label),
+                            Value::from(value),
+                        )
+                    });
+                }
+            }
+        }
+
+        anomalies
+    }
+}
@@ -1,112 +1,781 @@
-use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
 
-pub fn validate_climate_payload(payload: &str) -> Result<(), String> {
-    let data: Value = serde_json::from_str(payload).map_err(|_| "invalidJson".to_string())?;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 
-    if data.get("temperature").is_some() {
-        validate_temperature(payload)?;
+use super::{PayloadValidator, Severity, Unit, ValidationReport, Violation};
+
+pub const CLIMATE_SCHEMA: &str = "climate";
+
+/// Optional payload key mapping field names to the unit they were reported in, e.g.
+/// `{"temperature": 77.0, "units": {"temperature": "°F"}}`.
+pub const UNITS_KEY: &str = "units";
+
+/// First byte of packed climate payloads. It never starts valid UTF-8, so packed and JSON
+/// payloads cannot be confused.
+pub const PACKED_MAGIC: u8 = 0xC1;
+pub const PACKED_VERSION: u8 = 1;
+
+pub fn is_packed(payload: &[u8]) -> bool {
+    payload.first() == Some(&PACKED_MAGIC)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClimateField {
+    pub name: &'static str,
+    /// Prefix used for this field's error codes.
+    pub label: &'static str,
+    pub unit: &'static str,
+    pub min: f64,
+    pub max: f64,
+    pub code: &'static str,
+}
+
+pub const CLIMATE_FIELDS: [ClimateField; 15] = [
+    ClimateField {
+        name: "temperature",
+        label: "temperature",
+        unit: "°C",
+        min: -100.0,
+        max: 150.0,
+        code: "temperatureOutOfRange",
+    },
+    ClimateField {
+        name: "humidity",
+        label: "humidity",
+        unit: "%",
+        min: 0.0,
+        max: 100.0,
+        code: "humidityOutOfRange",
+    },
+    ClimateField {
+        name: "pressure",
+        label: "pressure",
+        unit: "hPa",
+        min: 300.0,
+        max: 1100.0,
+        code: "pressureOutOfRange",
+    },
+    ClimateField {
+        name: "dew_point",
+        label: "dewPoint",
+        unit: "°C",
+        min: -100.0,
+        max: 150.0,
+        code: "dewPointOutOfRange",
+    },
+    ClimateField {
+        name: "wind_speed",
+        label: "windSpeed",
+        unit: "m/s",
+        min: 0.0,
+        max: 100.0,
+        code: "windSpeedOutOfRange",
+    },
+    ClimateField {
+        name: "wind_direction",
+        label: "windDirection",
+        unit: "°",
+        min: 0.0,
+        max: 360.0,
+        code: "windDirectionOutOfRange",
+    },
+    ClimateField {
+        name: "rainfall",
+        label: "rainfall",
+        unit: "mm",
+        min: 0.0,
+        max: 50.0,
+        code: "rainfallOutOfRange",
+    },
+    ClimateField {
+        name: "uv_index",
+        label: "uvIndex",
+        unit: "",
+        min: 0.0,
+        max: 15.0,
+        code: "uvIndexTooHigh",
+    },
+    ClimateField {
+        name: "pm2_5",
+        label: "pm25",
+        unit: "µg/m³",
+        min: 0.0,
+        max: 1000.0,
+        code: "pm25OutOfRange",
+    },
+    ClimateField {
+        name: "pm10",
+        label: "pm10",
+        unit: "µg/m³",
+        min: 0.0,
+        max: 2000.0,
+        code: "pm10OutOfRange",
+    },
+    ClimateField {
+        name: "co2",
+        label: "co2",
+        unit: "ppm",
+        min: 0.0,
+        max: 10000.0,
+        code: "co2OutOfRange",
+    },
+    ClimateField {
+        name: "soil_moisture",
+        label: "soilMoisture",
+        unit: "%",
+        min: 0.0,
+        max: 100.0,
+        code: "soilMoistureOutOfRange",
+    },
+    ClimateField {
+        name: "solar_radiation",
+        label: "solarRadiation",
+        unit: "W/m²",
+        min: 0.0,
+        max: 2000.0,
+        code: "solarRadiationOutOfRange",
+    },
+    ClimateField {
+        name: "snow_depth",
+        label: "snowDepth",
+        unit: "cm",
+        min: 0.0,
+        max: 1000.0,
+        code: "snowDepthOutOfRange",
+    },
+    ClimateField {
+        name: "battery_voltage",
+        label: "batteryVoltage",
+        unit: "V",
+        min: 0.0,
+        max: 60.0,
+        code: "batteryVoltageOutOfRange",
+    },
+];
+
+pub fn climate_field(name: &str) -> Option<&'static ClimateField> {
+    CLIMATE_FIELDS.iter().find(|field| field.name == name)
+}
+
+impl ClimateField {
+    pub fn check(&self, value: f64) -> Result<(), String> {
+        self.violation(value)
+            .map_or(Ok(()), |violation| Err(violation.message()))
     }
-    if data.get("humidity").is_some() {
-        validate_humidity(payload)?;
+
+    pub fn violation(&self, value: f64) -> Option<Violation> {
+        self.violation_within(value, Bounds::new(self.min, self.max))
     }
-    if data.get("pressure").is_some() {
-        validate_pressure(payload)?;
+
+    pub fn violation_within(&self, value: f64, bounds: Bounds) -> Option<Violation> {
+        if !value.is_finite() {
+            return Some(Violation::error(
+                self.name,
+                format!("{}NotFinite", self.label),
+                Value::String(value.to_string()),
+            ));
+        }
+
+        if (bounds.min..=bounds.max).contains(&value) {
+            return None;
+        }
+
+        Some(Violation {
+            field: self.name.to_string(),
+            code: self.code.to_string(),
+            value: Value::from(value),
+            allowed: Some((bounds.min, bounds.max)),
+            severity: Severity::Error,
+        })
     }
-    if data.get("dew_point").is_some() {
-        validate_dew_point(payload)?;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Bounds {
+    pub min: f64,
+    pub max: f64,
+}
+
+impl Bounds {
+    pub fn new(min: f64, max: f64) -> Self {
+        Self { min, max }
     }
-    if data.get("wind_speed").is_some() {
-        validate_wind_speed(payload)?;
+}
+
+/// Accepted range for each climate field. Fields missing from a loaded configuration keep the
+/// defaults from [`CLIMATE_FIELDS`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClimateBounds {
+    ranges: BTreeMap<String, Bounds>,
+}
+
+impl Default for ClimateBounds {
+    fn default() -> Self {
+        let ranges = CLIMATE_FIELDS
+            .iter()
+            .map(|field| (field.name.to_string(), Bounds::new(field.min, field.max)))
+            .collect();
+        Self { ranges }
     }
-    if data.get("wind_direction").is_some() {
-        validate_wind_direction(payload)?;
+}
+
+impl ClimateBounds {
+    /// Reads overrides such as `{"rainfall": {"min": 0.0, "max": 300.0}}`.
+    pub fn from_json(config: &str) -> Result<Self, String> {
+        let overrides: BTreeMap<String, Bounds> = serde_json::from_str(config)
+            .map_err(|error| format!("climateBoundsInvalid: {}", error))?;
+
+        let mut bounds = Self::default();
+        for (name, range) in overrides {
+            bounds.set(&name, range)?;
+        }
+        Ok(bounds)
     }
-    if data.get("rainfall").is_some() {
-        validate_rainfall(payload)?;
+
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, String> {
+        let config = fs::read_to_string(path)
+            .map_err(|error| format!("climateBoundsUnreadable: {}", error))?;
+        Self::from_json(&config)
     }
-    if data.get("uv_index").is_some() {
-        validate_uv_index(payload)?;
+
+    pub fn get(&self, name: &str) -> Option<Bounds> {
+        self.ranges.get(name).copied()
     }
 
-    Ok(())
+    pub fn set(&mut self, name: &str, bounds: Bounds) -> Result<(), String> {
+        if climate_field(name).is_none() {
+            return Err(format!("climateBoundsUnknownField: {}", name));
+        }
+        if bounds.min.is_nan() || bounds.max.is_nan() || bounds.min > bounds.max {
+            return Err(format!(
+                "climateBoundsInvalid: {} (min: {}, max: {})",
+                name, bounds.min, bounds.max
+            ));
+        }
+        self.ranges.insert(name.to_string(), bounds);
+        Ok(())
+    }
 }
 
-pub fn validate_temperature(payload: &str) -> Result<(), String> {
-    let data: Value = serde_json::from_str(payload).map_err(|_| "invalidJson".to_string())?;
-    if let Some(temp) = data.get("temperature").and_then(|v| v.as_f64()) {
-        if !(-100.0..=150.0).contains(&temp) {
-            return Err(format!("temperatureOutOfRange:{}", temp));
+/// A climate measurement, in the units listed in [`CLIMATE_FIELDS`].
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ClimateReading {
+    /// Air temperature, °C.
+    pub temperature: Option<f64>,
+    /// Relative humidity, %.
+    pub humidity: Option<f64>,
+    /// Atmospheric pressure, hPa.
+    pub pressure: Option<f64>,
+    /// Dew point, °C.
+    pub dew_point: Option<f64>,
+    /// Wind speed, m/s.
+    pub wind_speed: Option<f64>,
+    /// Wind direction, degrees clockwise from north.
+    pub wind_direction: Option<f64>,
+    /// Rainfall, mm.
+    pub rainfall: Option<f64>,
+    /// UV index.
+    pub uv_index: Option<f64>,
+    /// Fine particulate matter, µg/m³.
+    pub pm2_5: Option<f64>,
+    /// Coarse particulate matter, µg/m³.
+    pub pm10: Option<f64>,
+    /// CO2 concentration, ppm.
+    pub co2: Option<f64>,
+    /// Volumetric soil moisture, %.
+    pub soil_moisture: Option<f64>,
+    /// Global solar radiation, W/m².
+    pub solar_radiation: Option<f64>,
+    /// Snow depth, cm.
+    pub snow_depth: Option<f64>,
+    /// Station battery voltage, V.
+    pub battery_voltage: Option<f64>,
+}
+
+impl ClimateReading {
+    pub fn from_json(payload: &str) -> Result<Self, String> {
+        let data: Value = serde_json::from_str(payload).map_err(|_| "invalidJson".to_string())?;
+        Ok(Self::from_value(&data))
+    }
+
+    pub fn from_value(data: &Value) -> Self {
+        let mut reading = Self::default();
+        for field in &CLIMATE_FIELDS {
+            if let Some(value) = data.get(field.name).and_then(Value::as_f64) {
+                reading.set(field.name, value);
+            }
         }
+        reading
     }
-    Ok(())
-}
 
-pub fn validate_humidity(payload: &str) -> Result<(), String> {
-    let data: Value = serde_json::from_str(payload).map_err(|_| "invalidJson".to_string())?;
-    if let Some(humidity) = data.get("humidity").and_then(|v| v.as_f64()) {
-        if !(0.0..=100.0).contains(&humidity) {
-            return Err(format!("humidityOutOfRange:{}", humidity));
+    pub fn to_json(&self) -> String {
+        let mut data = Map::new();
+        for (field, value) in self.values() {
+            data.insert(field.name.to_string(), Value::from(value));
+        }
+        Value::Object(data).to_string()
+    }
+
+    /// Compact encoding for constrained radios: magic byte, version, a little-endian `u16` mask
+    /// of the fields present, then one little-endian `f32` per present field, in
+    /// [`CLIMATE_FIELDS`] order. Values are in canonical units.
+    pub fn to_packed(&self) -> Vec<u8> {
+        let mut mask: u16 = 0;
+        let mut values = Vec::new();
+        for (index, field) in CLIMATE_FIELDS.iter().enumerate() {
+            if let Some(value) = self.get(field.name) {
+                mask |= 1 << index;
+                values.extend_from_slice(&(value as f32).to_le_bytes());
+            }
+        }
+
+        let mut packed = vec![PACKED_MAGIC, PACKED_VERSION];
+        packed.extend_from_slice(&mask.to_le_bytes());
+        packed.extend_from_slice(&values);
+        packed
+    }
+
+    pub fn from_packed(payload: &[u8]) -> Result<Self, String> {
+        let [magic, version, mask_low, mask_high, values @ ..] = payload else {
+            return Err("packedTruncated".to_string());
+        };
+        if *magic != PACKED_MAGIC {
+            return Err("packedInvalidHeader".to_string());
+        }
+        if *version != PACKED_VERSION {
+            return Err(format!("packedUnsupportedVersion:{}", version));
+        }
+
+        let mask = u16::from_le_bytes([*mask_low, *mask_high]);
+        if mask >> CLIMATE_FIELDS.len() != 0 {
+            return Err("packedUnknownField".to_string());
+        }
+
+        let mut chunks = values.chunks_exact(4);
+        let mut reading = Self::default();
+        for (index, field) in CLIMATE_FIELDS.iter().enumerate() {
+            if mask & (1 << index) == 0 {
+                continue;
+            }
+            let chunk = chunks.next().ok_or_else(|| "packedTruncated".to_string())?;
+            let value = f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+            reading.set(field.name, f64::from(value));
+        }
+
+        if chunks.next().is_some() || !chunks.remainder().is_empty() {
+            return Err("packedTrailingBytes".to_string());
+        }
+
+        Ok(reading)
+    }
+
+    pub fn get(&self, name: &str) -> Option<f64> {
+        match name {
+            "temperature" => self.temperature,
+            "humidity" => self.humidity,
+            "pressure" => self.pressure,
+            "dew_point" => self.dew_point,
+            "wind_speed" => self.wind_speed,
+            "wind_direction" => self.wind_direction,
+            "rainfall" => self.rainfall,
+            "uv_index" => self.uv_index,
+            "pm2_5" => self.pm2_5,
+            "pm10" => self.pm10,
+            "co2" => self.co2,
+            "soil_moisture" => self.soil_moisture,
+            "solar_radiation" => self.solar_radiation,
+            "snow_depth" => self.snow_depth,
+            "battery_voltage" => self.battery_voltage,
+            _ => None,
+        }
+    }
+
+    pub fn set(&mut self, name: &str, value: f64) -> bool {
+        let slot = match name {
+            "temperature" => &mut self.temperature,
+            "humidity" => &mut self.humidity,
+            "pressure" => &mut self.pressure,
+            "dew_point" => &mut self.dew_point,
+            "wind_speed" => &mut self.wind_speed,
+            "wind_direction" => &mut self.wind_direction,
+            "rainfall" => &mut self.rainfall,
+            "uv_index" => &mut self.uv_index,
+            "pm2_5" => &mut self.pm2_5,
+            "pm10" => &mut self.pm10,
+            "co2" => &mut self.co2,
+            "soil_moisture" => &mut self.soil_moisture,
+            "solar_radiation" => &mut self.solar_radiation,
+            "snow_depth" => &mut self.snow_depth,
+            "battery_voltage" => &mut self.battery_voltage,
+            _ => return false,
+        };
+        *slot = Some(value);
+        true
+    }
+
+    pub fn values(&self) -> Vec<(&'static ClimateField, f64)> {
+        CLIMATE_FIELDS
+            .iter()
+            .filter_map(|field| self.get(field.name).map(|value| (field, value)))
+            .collect()
+    }
+
+    pub fn report(&self) -> ValidationReport {
+        self.report_within(&ClimateBounds::default())
+    }
+
+    pub fn report_within(&self, bounds: &ClimateBounds) -> ValidationReport {
+        let mut report = ValidationReport::default();
+        for (field, value) in self.values() {
+            let range = bounds
+                .get(field.name)
+                .unwrap_or(Bounds::new(field.min, field.max));
+            if let Some(violation) = field.violation_within(value, range) {
+                report.push(violation);
+            }
+        }
+        report
+    }
+
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let violations: Vec<String> = self.report().errors().map(Violation::message).collect();
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(violations)
         }
     }
-    Ok(())
 }
 
-pub fn validate_pressure(payload: &str) -> Result<(), String> {
-    let data: Value = serde_json::from_str(payload).map_err(|_| "invalidJson".to_string())?;
-    if let Some(pressure) = data.get("pressure").and_then(|v| v.as_f64()) {
-        if !(300.0..=1100.0).contains(&pressure) {
-            return Err(format!("pressureOutOfRange:{}", pressure));
+/// Physical-plausibility rules across fields. Each rule reports at the given severity, or is
+/// skipped when set to `None`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConsistencyRules {
+    pub dew_point_above_temperature: Option<Severity>,
+    pub wind_direction_without_speed: Option<Severity>,
+    pub rainfall_without_humidity: Option<Severity>,
+}
+
+impl Default for ConsistencyRules {
+    fn default() -> Self {
+        Self {
+            dew_point_above_temperature: Some(Severity::Error),
+            wind_direction_without_speed: Some(Severity::Warning),
+            rainfall_without_humidity: Some(Severity::Warning),
         }
     }
-    Ok(())
 }
 
-pub fn validate_dew_point(payload: &str) -> Result<(), String> {
-    let data: Value = serde_json::from_str(payload).map_err(|_| "invalidJson".to_string())?;
-    if let Some(dew_point) = data.get("dew_point").and_then(|v| v.as_f64()) {
-        if !(-100.0..=150.0).contains(&dew_point) {
-            return Err(format!("dewPointOutOfRange:{}", dew_point));
+impl ConsistencyRules {
+    pub fn none() -> Self {
+        Self {
+            dew_point_above_temperature: None,
+            wind_direction_without_speed: None,
+            rainfall_without_humidity: None,
         }
     }
-    Ok(())
 }
 
-pub fn validate_wind_speed(payload: &str) -> Result<(), String> {
-    let data: Value = serde_json::from_str(payload).map_err(|_| "invalidJson".to_string())?;
-    if let Some(wind_speed) = data.get("wind_speed").and_then(|v| v.as_f64()) {
-        if !(0.0..=100.0).contains(&wind_speed) {
-            return Err(format!("windSpeedOutOfRange:{}", wind_speed));
+impl ClimateReading {
+    pub fn check_consistency(&self, rules: &ConsistencyRules) -> Vec<Violation> {
+        let mut violations = Vec::new();
+        let mut flag = |severity: Option<Severity>, field: &str, code: &str, value: f64| {
+            if let Some(severity) = severity {
+                violations.push(Violation {
+                    severity,
+                    ..Violation::error(field, code, Value::from(value))
+                });
+            }
+        };
+
+        if let (Some(dew_point), Some(temperature)) = (self.dew_point, self.temperature) {
+            if dew_point > temperature {
+                flag(
+                    rules.dew_point_above_temperature,
+                    "dew_point",
+                    "dewPointAboveTemperature",
+                    dew_point,
+                );
+            }
+        }
+
+        if let (Some(direction), Some(speed)) = (self.wind_direction, self.wind_speed) {
+            if speed == 0.0 && direction != 0.0 {
+                flag(
+                    rules.wind_direction_without_speed,
+                    "wind_direction",
+                    "windDirectionWithoutSpeed",
+                    direction,
+                );
+            }
         }
+
+        if let (Some(rainfall), Some(humidity)) = (self.rainfall, self.humidity) {
+            if rainfall > 0.0 && humidity == 0.0 {
+                flag(
+                    rules.rainfall_without_humidity,
+                    "rainfall",
+                    "rainfallWithoutHumidity",
+                    rainfall,
+                );
+            }
+        }
+
+        violations
     }
-    Ok(())
 }
 
-pub fn validate_wind_direction(payload: &str) -> Result<(), String> {
-    let data: Value = serde_json::from_str(payload).map_err(|_| "invalidJson".to_string())?;
-    if let Some(wind_direction) = data.get("wind_direction").and_then(|v| v.as_f64()) {
-        if !(0.0..=360.0).contains(&wind_direction) {
-            return Err(format!("windDirectionOutOfRange:{}", wind_direction));
+/// A reading converted to the canonical units of [`CLIMATE_FIELDS`], along with the values and
+/// units as reported by the station.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct NormalizedReading {
+    pub reading: ClimateReading,
+    pub original: ClimateReading,
+    pub units: BTreeMap<String, String>,
+}
+
+impl NormalizedReading {
+    pub fn from_value(data: &Value) -> Result<Self, String> {
+        let original = ClimateReading::from_value(data);
+        let mut normalized = Self {
+            reading: original,
+            original,
+            units: BTreeMap::new(),
+        };
+
+        let Some(units) = data.get(UNITS_KEY) else {
+            return Ok(normalized);
+        };
+        let units = units
+            .as_object()
+            .ok_or_else(|| "unitsInvalidType".to_string())?;
+
+        for (name, symbol) in units {
+            let field = climate_field(name).ok_or_else(|| format!("unitsUnknownField:{}", name))?;
+            let symbol = symbol
+                .as_str()
+                .ok_or_else(|| format!("{}UnitInvalidType", field.label))?;
+            // The canonical symbol needs no conversion, including for units `Unit` cannot parse.
+            if symbol == field.unit {
+                normalized.units.insert(name.clone(), symbol.to_string());
+                continue;
+            }
+            let unit = Unit::parse(symbol)
+                .ok_or_else(|| format!("{}UnitUnknown:{}", field.label, symbol))?;
+            // Fields without a convertible canonical unit (%, ppm, ...) must already use it.
+            let canonical = Unit::parse(field.unit);
+
+            if let Some(value) = original.get(name) {
+                let converted = match canonical {
+                    Some(canonical) => unit
+                        .convert(value, canonical)
+                        .map_err(|_| format!("{}UnitMismatch:{}", field.label, symbol))?,
+                    None => return Err(format!("{}UnitMismatch:{}", field.label, symbol)),
+                };
+                normalized.reading.set(name, converted);
+            }
+            normalized.units.insert(name.clone(), symbol.to_string());
         }
+
+        Ok(normalized)
     }
-    Ok(())
 }
 
-pub fn validate_rainfall(payload: &str) -> Result<(), String> {
-    let data: Value = serde_json::from_str(payload).map_err(|_| "invalidJson".to_string())?;
-    if let Some(rainfall) = data.get("rainfall").and_then(|v| v.as_f64()) {
-        if !(0.0..=50.0).contains(&rainfall) {
-            return Err(format!("rainfallOutOfRange:{}", rainfall));
+/// Decodes a JSON or packed climate payload into canonical units.
+pub fn normalize_climate_payload(payload: impl AsRef<[u8]>) -> Result<NormalizedReading, String> {
+    let payload = payload.as_ref();
+    if is_packed(payload) {
+        let reading = ClimateReading::from_packed(payload)?;
+        return Ok(NormalizedReading {
+            reading,
+            original: reading,
+            units: BTreeMap::new(),
+        });
+    }
+
+    let data: Value = serde_json::from_slice(payload).map_err(|_| "invalidJson".to_string())?;
+    NormalizedReading::from_value(&data)
+}
+
+impl From<ClimateReading> for Vec<u8> {
+    fn from(reading: ClimateReading) -> Self {
+        reading.to_json().into_bytes()
+    }
+}
+
+/// `Lenient` skips fields whose JSON type is not a number and ignores fields it does not know,
+/// `Strict` reports both.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ValidationMode {
+    #[default]
+    Lenient,
+    Strict,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ClimateValidator {
+    pub mode: ValidationMode,
+    pub consistency: ConsistencyRules,
+    pub bounds: ClimateBounds,
+}
+
+impl ClimateValidator {
+    pub fn strict() -> Self {
+        Self {
+            mode: ValidationMode::Strict,
+            ..Self::default()
+        }
+    }
+
+    pub fn with_bounds(bounds: ClimateBounds) -> Self {
+        Self {
+            bounds,
+            ..Self::default()
         }
     }
-    Ok(())
+
+    pub fn report(&self, payload: impl AsRef<[u8]>) -> ValidationReport {
+        let payload = payload.as_ref();
+        let mut report = ValidationReport::default();
+
+        let reading = if is_packed(payload) {
+            match ClimateReading::from_packed(payload) {
+                Ok(reading) => reading,
+                Err(code) => {
+                    report.push(Violation::error("payload", code, Value::Null));
+                    return report;
+                }
+            }
+        } else {
+            let data: Value = match serde_json::from_slice(payload) {
+                Ok(data) => data,
+                Err(_) => {
+                    report.push(Violation::error("payload", "invalidJson", Value::Null));
+                    return report;
+                }
+            };
+
+            if self.mode == ValidationMode::Strict {
+                check_types(&data, &mut report);
+            }
+
+            match NormalizedReading::from_value(&data) {
+                Ok(normalized) => normalized.reading,
+                Err(code) => {
+                    report.push(Violation::error(UNITS_KEY, code, Value::Null));
+                    return report;
+                }
+            }
+        };
+        report
+            .violations
+            .extend(reading.report_within(&self.bounds).violations);
+        report
+            .violations
+            .extend(reading.check_consistency(&self.consistency));
+        report
+    }
+}
+
+impl PayloadValidator for ClimateValidator {
+    fn validate(&self, payload: &[u8]) -> Result<(), String> {
+        self.report(payload).into_result()
+    }
+}
+
+pub fn validate_climate_payload(payload: impl AsRef<[u8]>) -> Result<(), String> {
+    climate_payload_report(payload).into_result()
+}
+
+/// Validates every field of the payload and returns all problems found, instead of stopping at
+/// the first one.
+pub fn climate_payload_report(payload: impl AsRef<[u8]>) -> ValidationReport {
+    climate_payload_report_with(payload, ValidationMode::Lenient)
 }
 
-pub fn validate_uv_index(payload: &str) -> Result<(), String> {
-    let data: Value = serde_json::from_str(payload).map_err(|_| "invalidJson".to_string())?;
-    if let Some(uv_index) = data.get("uv_index").and_then(|v| v.as_u64()) {
-        if uv_index > 15 {
-            return Err(format!("uvIndexTooHigh:{}", uv_index));
+/// Field-level checks only: types (in strict mode) and ranges, without consistency rules.
+pub fn climate_payload_report_with(
+    payload: impl AsRef<[u8]>,
+    mode: ValidationMode,
+) -> ValidationReport {
+    ClimateValidator {
+        mode,
+        consistency: ConsistencyRules::none(),
+        bounds: ClimateBounds::default(),
+    }
+    .report(payload)
+}
+
+fn check_types(data: &Value, report: &mut ValidationReport) {
+    let Some(object) = data.as_object() else {
+        report.push(Violation::error(
+            "payload",
+            "payloadNotObject",
+            data.clone(),
+        ));
+        return;
+    };
+
+    for (name, value) in object {
+        if name == UNITS_KEY {
+            continue;
         }
+        match climate_field(name) {
+            Some(field) if !value.is_number() => report.push(Violation::error(
+                name.as_str(),
+                format!("{}InvalidType", field.label),
+                value.clone(),
+            )),
+            Some(_) => {}
+            None => report.push(Violation::error(
+                name.as_str(),
+                "unknownField",
+                Value::String(name.clone()),
+            )),
+        }
+    }
+}
+
+fn validate_field(payload: impl AsRef<[u8]>, name: &str) -> Result<(), String> {
+    let reading = normalize_climate_payload(payload)?.reading;
+    match (climate_field(name), reading.get(name)) {
+        (Some(field), Some(value)) => field.check(value),
+        _ => Ok(()),
     }
-    Ok(())
+}
+
+pub fn validate_temperature(payload: impl AsRef<[u8]>) -> Result<(), String> {
+    validate_field(payload, "temperature")
+}
+
+pub fn validate_humidity(payload: impl AsRef<[u8]>) -> Result<(), String> {
+    validate_field(payload, "humidity")
+}
+
+pub fn validate_pressure(payload: impl AsRef<[u8]>) -> Result<(), String> {
+    validate_field(payload, "pressure")
+}
+
+pub fn validate_dew_point(payload: impl AsRef<[u8]>) -> Result<(), String> {
+    validate_field(payload, "dew_point")
+}
+
+pub fn validate_wind_speed(payload: impl AsRef<[u8]>) -> Result<(), String> {
+    validate_field(payload, "wind_speed")
+}
+
+pub fn validate_wind_direction(payload: impl AsRef<[u8]>) -> Result<(), String> {
+    validate_field(payload, "wind_direction")
+}
+
+pub fn validate_rainfall(payload: impl AsRef<[u8]>) -> Result<(), String> {
+    validate_field(payload, "rainfall")
+}
+
+pub fn validate_uv_index(payload: impl AsRef<[u8]>) -> Result<(), String> {
+    validate_field(payload, "uv_index")
 }
@@ -1 +1,17 @@
+pub mod anomaly;
 pub mod climate;
+pub mod payload;
+pub mod policy;
+pub mod report;
+pub mod schema;
+pub mod timestamp;
+pub mod units;
+
+pub use anomaly::AnomalyDetector;
+pub use climate::ClimateValidator;
+pub use payload::PayloadValidator;
+pub use policy::{IdPolicy, ValidationPolicy};
+pub use report::{Severity, ValidationReport, Violation};
+pub use schema::{FieldSchema, FieldType, PayloadSchema, CLIMATE_JSON_SCHEMA};
+pub use timestamp::TimestampPolicy;
+pub use units::Unit;
@@ -0,0 +1,6 @@
+use std::fmt::Debug;
+
+/// Checks the payload of transactions tagged with a given schema before they enter the tangle.
+pub trait PayloadValidator: Debug + Send + Sync {
+    fn validate(&self, payload: &[u8]) -> Result<(), String>;
+}
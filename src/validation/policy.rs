@@ -0,0 +1,109 @@
+use serde_json::Value;
+
+use super::climate::{is_packed, ClimateReading};
+use super::TimestampPolicy;
+use crate::transaction::TransactionKind;
+
+pub const DEFAULT_MAX_PAYLOAD_SIZE: usize = 256;
+/// Control transactions (registrations and the like) carry keys and signatures, so they get
+/// more room than sensor readings regardless of `max_payload_size`.
+pub const MAX_CONTROL_PAYLOAD_SIZE: usize = 1024;
+/// Proof-of-work is counted in leading zero bits of a SHA-256 hash, so no transaction can
+/// have more.
+pub const MAX_POW_DIFFICULTY: u32 = 256;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IdPolicy {
+    pub min_length: usize,
+    pub max_length: Option<usize>,
+    /// Characters accepted on top of alphanumerics.
+    pub allowed_symbols: Vec<char>,
+}
+
+impl Default for IdPolicy {
+    fn default() -> Self {
+        Self {
+            min_length: 1,
+            max_length: None,
+            allowed_symbols: vec!['-'],
+        }
+    }
+}
+
+impl IdPolicy {
+    pub fn is_valid_format(&self, id: &str) -> bool {
+        id.chars()
+            .all(|c| c.is_alphanumeric() || self.allowed_symbols.contains(&c))
+    }
+
+    pub fn is_valid_length(&self, id: &str) -> bool {
+        let length = id.chars().count();
+        length >= self.min_length && self.max_length.is_none_or(|max| length <= max)
+    }
+}
+
+/// Limits applied to transactions. The defaults match a small LoRa sensor; gateways can raise
+/// them as needed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationPolicy {
+    pub max_payload_size: usize,
+    pub id: IdPolicy,
+    pub timestamps: TimestampPolicy,
+    /// When non-empty, data payloads must be JSON objects holding each of these fields.
+    pub required_fields: Vec<String>,
+    /// Leading zero bits required in a transaction's proof-of-work hash; 0 disables the check.
+    pub pow_difficulty: u32,
+}
+
+impl Default for ValidationPolicy {
+    fn default() -> Self {
+        Self {
+            max_payload_size: DEFAULT_MAX_PAYLOAD_SIZE,
+            id: IdPolicy::default(),
+            timestamps: TimestampPolicy::default(),
+            required_fields: Vec::new(),
+            pow_difficulty: 0,
+        }
+    }
+}
+
+impl ValidationPolicy {
+    pub fn max_payload_size_for(&self, kind: TransactionKind) -> usize {
+        match kind {
+            TransactionKind::Data => self.max_payload_size,
+            _ => self.max_payload_size.max(MAX_CONTROL_PAYLOAD_SIZE),
+        }
+    }
+
+    /// Control transactions have payloads of their own format and are exempt. Packed climate
+    /// readings are checked for the fields they carry.
+    pub fn check_required_fields(
+        &self,
+        kind: TransactionKind,
+        payload: &[u8],
+    ) -> Result<(), String> {
+        if self.required_fields.is_empty() || kind != TransactionKind::Data {
+            return Ok(());
+        }
+
+        let missing = if is_packed(payload) {
+            let reading = ClimateReading::from_packed(payload)
+                .map_err(|error| format!("transactionPayloadInvalid: {}", error))?;
+            self.required_fields
+                .iter()
+                .find(|field| reading.get(field).is_none())
+        } else {
+            let data: Value = serde_json::from_slice(payload)
+                .map_err(|_| "transactionPayloadNotJson".to_string())?;
+            self.required_fields
+                .iter()
+                .find(|field| data.get(field.as_str()).is_none())
+        };
+
+        if let Some(field) = missing {
+            return Err(format!("transactionPayloadMissingField: {}", field));
+        }
+
+        Ok(())
+    }
+}
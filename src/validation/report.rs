@@ -0,0 +1,75 @@
+use serde_json::Value;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Violation {
+    pub field: String,
+    pub code: String,
+    pub value: Value,
+    /// Inclusive `(min, max)` bounds the value had to fall within, when range-checked.
+    pub allowed: Option<(f64, f64)>,
+    pub severity: Severity,
+}
+
+impl Violation {
+    pub fn error(field: impl Into<String>, code: impl Into<String>, value: Value) -> Self {
+        Self {
+            field: field.into(),
+            code: code.into(),
+            value,
+            allowed: None,
+            severity: Severity::Error,
+        }
+    }
+
+    pub fn message(&self) -> String {
+        match &self.value {
+            Value::Null => self.code.clone(),
+            Value::Number(number) => match number.as_f64() {
+                Some(value) => format!("{}:{}", self.code, value),
+                None => format!("{}:{}", self.code, number),
+            },
+            Value::String(text) => format!("{}:{}", self.code, text),
+            value => format!("{}:{}", self.code, value),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ValidationReport {
+    pub violations: Vec<Violation>,
+}
+
+impl ValidationReport {
+    pub fn push(&mut self, violation: Violation) {
+        self.violations.push(violation);
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.errors().next().is_none()
+    }
+
+    pub fn errors(&self) -> impl Iterator<Item = &Violation> {
+        self.violations
+            .iter()
+            .filter(|violation| violation.severity == Severity::Error)
+    }
+
+    pub fn warnings(&self) -> impl Iterator<Item = &Violation> {
+        self.violations
+            .iter()
+            .filter(|violation| violation.severity == Severity::Warning)
+    }
+
+    pub fn into_result(self) -> Result<(), String> {
+        match self.errors().next() {
+            Some(violation) => Err(violation.message()),
+            None => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,189 @@
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::climate::CLIMATE_FIELDS;
+use super::{PayloadValidator, ValidationReport, Violation};
+
+/// Name of [`PayloadSchema::climate`]. It differs from the built-in `climate` schema, whose
+/// validator also accepts packed readings and unit conversions a JSON schema cannot express.
+pub const CLIMATE_JSON_SCHEMA: &str = "climate-json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FieldType {
+    Number,
+    Integer,
+    String,
+    Boolean,
+}
+
+impl FieldType {
+    fn matches(&self, value: &Value) -> bool {
+        match self {
+            FieldType::Number => value.is_number(),
+            FieldType::Integer => value.is_i64() || value.is_u64(),
+            FieldType::String => value.is_string(),
+            FieldType::Boolean => value.is_boolean(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldSchema {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub field_type: FieldType,
+    #[serde(default)]
+    pub unit: Option<String>,
+    #[serde(default)]
+    pub min: Option<f64>,
+    #[serde(default)]
+    pub max: Option<f64>,
+    #[serde(default)]
+    pub required: bool,
+}
+
+/// Declarative description of a JSON payload, loadable at runtime, e.g.
+/// `{"name": "hydrology", "fields": [{"name": "flow", "type": "number", "unit": "m3/s", "min": 0}]}`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PayloadSchema {
+    pub name: String,
+    pub fields: Vec<FieldSchema>,
+    #[serde(default = "allow_unknown_fields_default")]
+    pub allow_unknown_fields: bool,
+}
+
+fn allow_unknown_fields_default() -> bool {
+    true
+}
+
+/// `dew_point` becomes `dewPoint`, matching the error codes used elsewhere.
+fn error_label(name: &str) -> String {
+    let mut label = String::with_capacity(name.len());
+    let mut upper = false;
+    for c in name.chars() {
+        if c == '_' {
+            upper = true;
+        } else if upper {
+            label.extend(c.to_uppercase());
+            upper = false;
+        } else {
+            label.push(c);
+        }
+    }
+    label
+}
+
+impl PayloadSchema {
+    pub fn from_json(schema: &str) -> Result<Self, String> {
+        serde_json::from_str(schema).map_err(|error| format!("schemaInvalid: {}", error))
+    }
+
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, String> {
+        let schema =
+            fs::read_to_string(path).map_err(|error| format!("schemaUnreadable: {}", error))?;
+        Self::from_json(&schema)
+    }
+
+    /// The built-in climate rules expressed as a schema.
+    pub fn climate() -> Self {
+        let fields = CLIMATE_FIELDS
+            .iter()
+            .map(|field| FieldSchema {
+                name: field.name.to_string(),
+                field_type: FieldType::Number,
+                unit: (!field.unit.is_empty()).then(|| field.unit.to_string()),
+                min: Some(field.min),
+                max: Some(field.max),
+                required: false,
+            })
+            .collect();
+
+        Self {
+            name: CLIMATE_JSON_SCHEMA.to_string(),
+            fields,
+            allow_unknown_fields: true,
+        }
+    }
+
+    pub fn field(&self, name: &str) -> Option<&FieldSchema> {
+        self.fields.iter().find(|field| field.name == name)
+    }
+
+    pub fn report(&self, payload: impl AsRef<[u8]>) -> ValidationReport {
+        let mut report = ValidationReport::default();
+
+        let data: Value = match serde_json::from_slice(payload.as_ref()) {
+            Ok(data) => data,
+            Err(_) => {
+                report.push(Violation::error("payload", "invalidJson", Value::Null));
+                return report;
+            }
+        };
+        let Some(object) = data.as_object() else {
+            report.push(Violation::error("payload", "payloadNotObject", data));
+            return report;
+        };
+
+        for field in &self.fields {
+            let label = error_label(&field.name);
+            let Some(value) = object.get(&field.name) else {
+                if field.required {
+                    report.push(Violation::error(
+                        field.name.as_str(),
+                        format!("{}Missing", label),
+                        Value::Null,
+                    ));
+                }
+                continue;
+            };
+
+            if !field.field_type.matches(value) {
+                report.push(Violation::error(
+                    field.name.as_str(),
+                    format!("{}InvalidType", label),
+                    value.clone(),
+                ));
+                continue;
+            }
+
+            if let Some(number) = value.as_f64() {
+                let min = field.min.unwrap_or(f64::NEG_INFINITY);
+                let max = field.max.unwrap_or(f64::INFINITY);
+                if !(min..=max).contains(&number) {
+                    report.push(Violation {
+                        allowed: Some((min, max)),
+                        ..Violation::error(
+                            field.name.as_str(),
+                            format!("{}OutOfRange", label),
+                            value.clone(),
+                        )
+                    });
+                }
+            }
+        }
+
+        if !self.allow_unknown_fields {
+            for name in object.keys() {
+                if self.field(name).is_none() {
+                    report.push(Violation::error(
+                        name.as_str(),
+                        "unknownField",
+                        Value::String(name.clone()),
+                    ));
+                }
+            }
+        }
+
+        report
+    }
+}
+
+impl PayloadValidator for PayloadSchema {
+    fn validate(&self, payload: &[u8]) -> Result<(), String> {
+        self.report(payload).into_result()
+    }
+}
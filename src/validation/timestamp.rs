@@ -0,0 +1,35 @@
+/// Accepted distance between a transaction timestamp and the local clock, in milliseconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TimestampPolicy {
+    pub max_future_skew_ms: u64,
+    pub max_age_ms: Option<u64>,
+}
+
+impl TimestampPolicy {
+    pub fn new(max_future_skew_ms: u64, max_age_ms: Option<u64>) -> Self {
+        Self {
+            max_future_skew_ms,
+            max_age_ms,
+        }
+    }
+
+    pub fn check(&self, timestamp: u64, now: u64) -> Result<(), String> {
+        if timestamp > now.saturating_add(self.max_future_skew_ms) {
+            return Err(format!(
+                "transactionTimestampInvalid: {} (now: {})",
+                timestamp, now
+            ));
+        }
+
+        if let Some(max_age_ms) = self.max_age_ms {
+            if now.saturating_sub(timestamp) > max_age_ms {
+                return Err(format!(
+                    "transactionTimestampTooOld: {} (now: {}, max age: {} ms)",
+                    timestamp, now, max_age_ms
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
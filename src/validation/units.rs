@@ -0,0 +1,126 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Quantity {
+    Temperature,
+    Pressure,
+    Speed,
+    Length,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unit {
+    Celsius,
+    Fahrenheit,
+    Kelvin,
+    HectoPascal,
+    KiloPascal,
+    InchOfMercury,
+    MetersPerSecond,
+    KilometersPerHour,
+    MilesPerHour,
+    Knots,
+    Millimeters,
+    Centimeters,
+    Inches,
+}
+
+impl Unit {
+    pub fn parse(symbol: &str) -> Option<Self> {
+        let unit = match symbol {
+            "°C" | "C" | "degC" => Unit::Celsius,
+            "°F" | "F" | "degF" => Unit::Fahrenheit,
+            "K" => Unit::Kelvin,
+            "hPa" | "mbar" => Unit::HectoPascal,
+            "kPa" => Unit::KiloPascal,
+            "inHg" => Unit::InchOfMercury,
+            "m/s" => Unit::MetersPerSecond,
+            "km/h" => Unit::KilometersPerHour,
+            "mph" => Unit::MilesPerHour,
+            "kn" | "kt" => Unit::Knots,
+            "mm" => Unit::Millimeters,
+            "cm" => Unit::Centimeters,
+            "in" => Unit::Inches,
+            _ => return None,
+        };
+        Some(unit)
+    }
+
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Unit::Celsius => "°C",
+            Unit::Fahrenheit => "°F",
+            Unit::Kelvin => "K",
+            Unit::HectoPascal => "hPa",
+            Unit::KiloPascal => "kPa",
+            Unit::InchOfMercury => "inHg",
+            Unit::MetersPerSecond => "m/s",
+            Unit::KilometersPerHour => "km/h",
+            Unit::MilesPerHour => "mph",
+            Unit::Knots => "kn",
+            Unit::Millimeters => "mm",
+            Unit::Centimeters => "cm",
+            Unit::Inches => "in",
+        }
+    }
+
+    pub fn quantity(&self) -> Quantity {
+        match self {
+            Unit::Celsius | Unit::Fahrenheit | Unit::Kelvin => Quantity::Temperature,
+            Unit::HectoPascal | Unit::KiloPascal | Unit::InchOfMercury => Quantity::Pressure,
+            Unit::MetersPerSecond | Unit::KilometersPerHour | Unit::MilesPerHour | Unit::Knots => {
+                Quantity::Speed
+            }
+            Unit::Millimeters | Unit::Centimeters | Unit::Inches => Quantity::Length,
+        }
+    }
+
+    /// Value expressed in the SI unit of its quantity (K, Pa, m/s, m).
+    fn to_si(self, value: f64) -> f64 {
+        match self {
+            Unit::Celsius => value + 273.15,
+            Unit::Fahrenheit => (value - 32.0) * 5.0 / 9.0 + 273.15,
+            Unit::Kelvin => value,
+            Unit::HectoPascal => value * 100.0,
+            Unit::KiloPascal => value * 1000.0,
+            Unit::InchOfMercury => value * 3386.389,
+            Unit::MetersPerSecond => value,
+            Unit::KilometersPerHour => value / 3.6,
+            Unit::MilesPerHour => value * 0.44704,
+            Unit::Knots => value * 1852.0 / 3600.0,
+            Unit::Millimeters => value / 1000.0,
+            Unit::Centimeters => value / 100.0,
+            Unit::Inches => value * 0.0254,
+        }
+    }
+
+    fn si_to_unit(self, value: f64) -> f64 {
+        match self {
+            Unit::Celsius => value - 273.15,
+            Unit::Fahrenheit => (value - 273.15) * 9.0 / 5.0 + 32.0,
+            Unit::Kelvin => value,
+            Unit::HectoPascal => value / 100.0,
+            Unit::KiloPascal => value / 1000.0,
+            Unit::InchOfMercury => value / 3386.389,
+            Unit::MetersPerSecond => value,
+            Unit::KilometersPerHour => value * 3.6,
+            Unit::MilesPerHour => value / 0.44704,
+            Unit::Knots => value * 3600.0 / 1852.0,
+            Unit::Millimeters => value * 1000.0,
+            Unit::Centimeters => value * 100.0,
+            Unit::Inches => value / 0.0254,
+        }
+    }
+
+    pub fn convert(&self, value: f64, to: Unit) -> Result<f64, String> {
+        if self.quantity() != to.quantity() {
+            return Err(format!(
+                "unitMismatch: {} -> {}",
+                self.symbol(),
+                to.symbol()
+            ));
+        }
+        if *self == to {
+            return Ok(value);
+        }
+        Ok(to.si_to_unit(self.to_si(value)))
+    }
+}
@@ -1,7 +1,12 @@
 use eco_weave::validation::climate::{
+    climate_payload_report, climate_payload_report_with, validate_climate_payload,
     validate_dew_point, validate_humidity, validate_pressure, validate_rainfall,
-    validate_temperature, validate_uv_index, validate_wind_direction, validate_wind_speed,
+    validate_temperature, validate_uv_index, validate_wind_direction, validate_wind_speed, Bounds,
+    ClimateBounds, ClimateReading, ClimateValidator, ConsistencyRules, ValidationMode,
 };
+use eco_weave::validation::climate::{is_packed, normalize_climate_payload, CLIMATE_FIELDS};
+use eco_weave::validation::{PayloadValidator, Severity, Unit};
+use eco_weave::Transaction;
 
 #[test]
 fn test_validate_temperature() {
@@ -89,3 +94,384 @@ fn test_invalid_json_format() {
     let err = validate_temperature(invalid_payload).unwrap_err();
     assert_eq!(err, "invalidJson");
 }
+
+#[test]
+fn test_climate_reading_round_trip() {
+    let reading = ClimateReading {
+        temperature: Some(25.6),
+        humidity: Some(60.5),
+        uv_index: Some(7.0),
+        ..ClimateReading::default()
+    };
+
+    let payload = reading.to_json();
+    assert_eq!(ClimateReading::from_json(&payload).unwrap(), reading);
+    assert_eq!(reading.get("humidity"), Some(60.5));
+    assert_eq!(reading.get("pressure"), None);
+}
+
+#[test]
+fn test_climate_reading_reports_all_violations() {
+    let payload = r#"{"temperature": 200.0, "humidity": 50.0, "pressure": 250.0, "uv_index": 20}"#;
+    let reading = ClimateReading::from_json(payload).unwrap();
+
+    assert_eq!(
+        reading.validate().unwrap_err(),
+        vec![
+            "temperatureOutOfRange:200",
+            "pressureOutOfRange:250",
+            "uvIndexTooHigh:20"
+        ]
+    );
+    assert_eq!(
+        validate_climate_payload(payload).unwrap_err(),
+        "temperatureOutOfRange:200"
+    );
+}
+
+#[test]
+fn test_climate_reading_as_transaction_payload() {
+    let reading = ClimateReading {
+        temperature: Some(25.6),
+        ..ClimateReading::default()
+    };
+
+    let tx = Transaction::new("tx1", reading).unwrap();
+    assert_eq!(tx.payload_str(), Some(r#"{"temperature":25.6}"#));
+    assert!(validate_climate_payload(&tx.payload).is_ok());
+}
+
+#[test]
+fn test_climate_payload_report_lists_every_field() {
+    let payload =
+        r#"{"temperature": 21.0, "humidity": 120.0, "wind_speed": -3.0, "rainfall": 80.0}"#;
+    let report = climate_payload_report(payload);
+
+    assert!(!report.is_valid());
+    let fields: Vec<&str> = report.errors().map(|v| v.field.as_str()).collect();
+    assert_eq!(fields, vec!["humidity", "wind_speed", "rainfall"]);
+
+    let rainfall = &report.violations[2];
+    assert_eq!(rainfall.code, "rainfallOutOfRange");
+    assert_eq!(rainfall.value, serde_json::json!(80.0));
+    assert_eq!(rainfall.allowed, Some((0.0, 50.0)));
+    assert_eq!(rainfall.severity, Severity::Error);
+    assert_eq!(rainfall.message(), "rainfallOutOfRange:80");
+
+    let malformed = climate_payload_report("{invalid_json}");
+    assert_eq!(malformed.violations.len(), 1);
+    assert_eq!(malformed.violations[0].message(), "invalidJson");
+}
+
+#[test]
+fn test_strict_mode_rejects_malformed_types() {
+    let payload =
+        r#"{"temperature": "hot", "humidity": null, "pressure": 1013.0, "station": "roof"}"#;
+
+    assert!(validate_climate_payload(payload).is_ok());
+
+    let report = climate_payload_report_with(payload, ValidationMode::Strict);
+    let messages: Vec<String> = report.errors().map(|v| v.message()).collect();
+    assert_eq!(
+        messages,
+        vec![
+            "humidityInvalidType",
+            "unknownField:station",
+            "temperatureInvalidType:hot"
+        ]
+    );
+    assert_eq!(
+        ClimateValidator::strict()
+            .validate(payload.as_bytes())
+            .unwrap_err(),
+        "humidityInvalidType"
+    );
+}
+
+#[test]
+fn test_uv_index_accepts_fractional_values() {
+    assert!(validate_uv_index(r#"{"uv_index": 7.5}"#).is_ok());
+    assert_eq!(
+        validate_uv_index(r#"{"uv_index": 15.5}"#).unwrap_err(),
+        "uvIndexTooHigh:15.5"
+    );
+}
+
+#[test]
+fn test_non_finite_values_are_rejected() {
+    let reading = ClimateReading {
+        temperature: Some(f64::NAN),
+        wind_speed: Some(f64::INFINITY),
+        ..ClimateReading::default()
+    };
+
+    assert_eq!(
+        reading.validate().unwrap_err(),
+        vec!["temperatureNotFinite:NaN", "windSpeedNotFinite:inf"]
+    );
+}
+
+#[test]
+fn test_consistency_rules() {
+    let reading = ClimateReading {
+        temperature: Some(10.0),
+        dew_point: Some(14.0),
+        wind_speed: Some(0.0),
+        wind_direction: Some(270.0),
+        rainfall: Some(2.0),
+        humidity: Some(0.0),
+        ..ClimateReading::default()
+    };
+    let payload = reading.to_json();
+
+    // Each value is within its own range.
+    assert!(validate_climate_payload(&payload).is_ok());
+
+    let report = ClimateValidator::default().report(&payload);
+    let errors: Vec<&str> = report.errors().map(|v| v.code.as_str()).collect();
+    let warnings: Vec<&str> = report.warnings().map(|v| v.code.as_str()).collect();
+    assert_eq!(errors, vec!["dewPointAboveTemperature"]);
+    assert_eq!(
+        warnings,
+        vec!["windDirectionWithoutSpeed", "rainfallWithoutHumidity"]
+    );
+    assert_eq!(
+        ClimateValidator::default()
+            .validate(payload.as_bytes())
+            .unwrap_err(),
+        "dewPointAboveTemperature:14"
+    );
+}
+
+#[test]
+fn test_consistency_rules_are_configurable() {
+    let payload =
+        r#"{"temperature": 10.0, "dew_point": 14.0, "wind_speed": 0.0, "wind_direction": 90.0}"#;
+
+    let relaxed = ClimateValidator {
+        consistency: ConsistencyRules {
+            dew_point_above_temperature: Some(Severity::Warning),
+            wind_direction_without_speed: None,
+            ..ConsistencyRules::default()
+        },
+        ..ClimateValidator::default()
+    };
+    let report = relaxed.report(payload);
+
+    assert!(report.is_valid());
+    assert_eq!(report.warnings().count(), 1);
+    assert!(relaxed.validate(payload.as_bytes()).is_ok());
+}
+
+#[test]
+fn test_configurable_climate_bounds() {
+    let payload = r#"{"rainfall": 120.0, "wind_speed": 40.0}"#;
+    assert_eq!(
+        validate_climate_payload(payload).unwrap_err(),
+        "rainfallOutOfRange:120"
+    );
+
+    let tropical = ClimateBounds::from_json(r#"{"rainfall": {"min": 0.0, "max": 300.0}}"#).unwrap();
+    assert_eq!(tropical.get("rainfall"), Some(Bounds::new(0.0, 300.0)));
+    // Fields not mentioned in the configuration keep their defaults.
+    assert_eq!(tropical.get("wind_speed"), Some(Bounds::new(0.0, 100.0)));
+
+    let validator = ClimateValidator::with_bounds(tropical);
+    assert!(validator.validate(payload.as_bytes()).is_ok());
+    assert_eq!(
+        validator.report(r#"{"rainfall": 350.0}"#).violations[0].allowed,
+        Some((0.0, 300.0))
+    );
+}
+
+#[test]
+fn test_invalid_climate_bounds_configuration() {
+    assert_eq!(
+        ClimateBounds::from_json(r#"{"visibility": {"min": 0.0, "max": 10.0}}"#).unwrap_err(),
+        "climateBoundsUnknownField: visibility"
+    );
+    assert!(ClimateBounds::from_json(r#"{"rainfall": {"min": 10.0, "max": 1.0}}"#).is_err());
+    assert!(ClimateBounds::from_json("not json").is_err());
+}
+
+#[test]
+fn test_additional_measurement_fields() {
+    let reading = ClimateReading {
+        pm2_5: Some(12.0),
+        pm10: Some(30.0),
+        co2: Some(415.0),
+        soil_moisture: Some(32.5),
+        solar_radiation: Some(650.0),
+        snow_depth: Some(0.0),
+        battery_voltage: Some(3.7),
+        ..ClimateReading::default()
+    };
+    assert!(reading.validate().is_ok());
+    assert_eq!(
+        ClimateReading::from_json(&reading.to_json()).unwrap(),
+        reading
+    );
+
+    let payload = r#"{"co2": -5.0, "battery_voltage": 75.0}"#;
+    let codes: Vec<String> = climate_payload_report(payload)
+        .errors()
+        .map(|v| v.message())
+        .collect();
+    assert_eq!(
+        codes,
+        vec!["co2OutOfRange:-5", "batteryVoltageOutOfRange:75"]
+    );
+}
+
+#[test]
+fn test_unit_conversion() {
+    assert_eq!(
+        Unit::Fahrenheit.convert(212.0, Unit::Celsius).unwrap(),
+        100.0
+    );
+    assert!(
+        (Unit::InchOfMercury
+            .convert(29.92, Unit::HectoPascal)
+            .unwrap()
+            - 1013.2)
+            .abs()
+            < 0.1
+    );
+    assert!(
+        (Unit::MilesPerHour
+            .convert(10.0, Unit::MetersPerSecond)
+            .unwrap()
+            - 4.4704)
+            .abs()
+            < 1e-9
+    );
+    assert_eq!(
+        Unit::MilesPerHour.convert(10.0, Unit::Celsius).unwrap_err(),
+        "unitMismatch: mph -> °C"
+    );
+    assert_eq!(Unit::parse("inHg"), Some(Unit::InchOfMercury));
+    assert_eq!(Unit::parse("furlong"), None);
+}
+
+#[test]
+fn test_normalize_climate_payload() {
+    let payload = r#"{
+        "temperature": 77.0,
+        "pressure": 29.92,
+        "wind_speed": 22.37,
+        "humidity": 40.0,
+        "units": {"temperature": "°F", "pressure": "inHg", "wind_speed": "mph"}
+    }"#;
+    let normalized = normalize_climate_payload(payload).unwrap();
+
+    assert!((normalized.reading.temperature.unwrap() - 25.0).abs() < 1e-9);
+    assert!((normalized.reading.pressure.unwrap() - 1013.2).abs() < 0.1);
+    assert!((normalized.reading.wind_speed.unwrap() - 10.0).abs() < 0.01);
+    assert_eq!(normalized.reading.humidity, Some(40.0));
+
+    // The values as reported are kept for audit.
+    assert_eq!(normalized.original.temperature, Some(77.0));
+    assert_eq!(normalized.units["pressure"], "inHg");
+
+    assert!(validate_climate_payload(payload).is_ok());
+    assert!(ClimateValidator::strict()
+        .validate(payload.as_bytes())
+        .is_ok());
+}
+
+#[test]
+fn test_canonical_unit_annotations_are_accepted() {
+    let mut data = serde_json::Map::new();
+    let mut units = serde_json::Map::new();
+    for field in CLIMATE_FIELDS {
+        data.insert(field.name.into(), ((field.min + field.max) / 2.0).into());
+        units.insert(field.name.into(), field.unit.into());
+    }
+    data.insert("units".into(), units.into());
+    let payload = serde_json::Value::Object(data).to_string();
+
+    let normalized = normalize_climate_payload(&payload).unwrap();
+    assert_eq!(normalized.reading, normalized.original);
+    assert_eq!(normalized.units.len(), CLIMATE_FIELDS.len());
+
+    assert!(validate_climate_payload(r#"{"humidity": 40, "units": {"humidity": "%"}}"#).is_ok());
+    assert!(validate_climate_payload(
+        r#"{"wind_direction": 180, "units": {"wind_direction": "°"}}"#
+    )
+    .is_ok());
+}
+
+#[test]
+fn test_ranges_apply_after_normalization() {
+    // 2 inches of rain is above the default 50 mm limit, 2 mm is not.
+    let report = climate_payload_report(r#"{"rainfall": 2.0, "units": {"rainfall": "in"}}"#);
+    assert_eq!(report.errors().next().unwrap().code, "rainfallOutOfRange");
+    assert!(validate_rainfall(r#"{"rainfall": 2.0, "units": {"rainfall": "mm"}}"#).is_ok());
+
+    assert_eq!(
+        validate_climate_payload(r#"{"humidity": 40.0, "units": {"humidity": "mph"}}"#)
+            .unwrap_err(),
+        "humidityUnitMismatch:mph"
+    );
+    assert_eq!(
+        validate_climate_payload(r#"{"temperature": 40.0, "units": {"temperature": "R"}}"#)
+            .unwrap_err(),
+        "temperatureUnitUnknown:R"
+    );
+}
+
+#[test]
+fn test_packed_climate_reading_round_trip() {
+    let reading = ClimateReading {
+        temperature: Some(25.5),
+        humidity: Some(60.25),
+        battery_voltage: Some(3.75),
+        ..ClimateReading::default()
+    };
+
+    let packed = reading.to_packed();
+    assert!(is_packed(&packed));
+    assert_eq!(packed.len(), 4 + 3 * 4);
+    assert!(packed.len() < reading.to_json().len());
+    assert_eq!(ClimateReading::from_packed(&packed).unwrap(), reading);
+
+    assert_eq!(
+        ClimateReading::from_packed(&packed[..packed.len() - 1]).unwrap_err(),
+        "packedTruncated"
+    );
+    let mut trailing = packed.clone();
+    trailing.push(0);
+    assert_eq!(
+        ClimateReading::from_packed(&trailing).unwrap_err(),
+        "packedTrailingBytes"
+    );
+}
+
+#[test]
+fn test_validators_accept_packed_and_json_payloads() {
+    let valid = ClimateReading {
+        temperature: Some(21.5),
+        ..ClimateReading::default()
+    };
+    let invalid = ClimateReading {
+        rainfall: Some(80.0),
+        ..ClimateReading::default()
+    };
+
+    assert!(validate_climate_payload(valid.to_packed()).is_ok());
+    assert!(validate_climate_payload(valid.to_json()).is_ok());
+    assert_eq!(
+        validate_climate_payload(invalid.to_packed()).unwrap_err(),
+        "rainfallOutOfRange:80"
+    );
+    assert_eq!(
+        normalize_climate_payload(valid.to_packed())
+            .unwrap()
+            .reading,
+        valid
+    );
+    assert_eq!(
+        validate_climate_payload([0xC1, 9, 0, 0]).unwrap_err(),
+        "packedUnsupportedVersion:9"
+    );
+}
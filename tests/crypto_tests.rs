@@ -0,0 +1,85 @@
+use eco_weave::{Algorithm, PublicKey, Signature, Signer, Transaction, Verifier};
+use ed25519_dalek::SigningKey;
+use rand::rngs::OsRng;
+
+#[test]
+fn test_keys_and_signatures_round_trip_through_hex() {
+    let ed25519_key = SigningKey::generate(&mut OsRng);
+    let p256_key = p256::ecdsa::SigningKey::random(&mut OsRng);
+
+    for signer in [&ed25519_key as &dyn Signer, &p256_key as &dyn Signer] {
+        let public_key = signer.public_key();
+        let signature = signer.sign_message(b"reading");
+
+        let parsed_key = PublicKey::from_hex(&public_key.to_hex()).unwrap();
+        let parsed_signature = Signature::from_hex(&signature.to_hex()).unwrap();
+        assert_eq!(parsed_key, public_key);
+        assert_eq!(parsed_signature, signature);
+        assert!(parsed_key
+            .verify_message(b"reading", &parsed_signature)
+            .is_ok());
+        assert!(parsed_key
+            .verify_message(b"tampered", &parsed_signature)
+            .is_err());
+    }
+
+    assert_eq!(p256_key.algorithm(), Algorithm::EcdsaP256);
+    assert!(p256_key.public_key().to_hex().starts_with("ecdsaP256:"));
+    assert_eq!(
+        PublicKey::from_hex("rsa:00").unwrap_err(),
+        "invalidPublicKey"
+    );
+}
+
+#[test]
+fn test_transaction_signed_with_p256() {
+    let signing_key = p256::ecdsa::SigningKey::random(&mut OsRng);
+    let ed25519_key = SigningKey::generate(&mut OsRng);
+
+    let mut tx = Transaction::new("tx1", r#"{"temperature": 25}"#).unwrap();
+    tx.sign(&signing_key);
+    assert_eq!(tx.algorithm, Algorithm::EcdsaP256);
+    assert!(tx.validate_signature(signing_key.verifying_key()).is_ok());
+    assert_eq!(
+        tx.validate_signature(&ed25519_key.verifying_key())
+            .unwrap_err(),
+        "signatureAlgorithmMismatch: ecdsaP256 (key: ed25519)"
+    );
+
+    // The algorithm is part of the signed data.
+    tx.algorithm = Algorithm::Ed25519;
+    assert!(tx
+        .validate_signature(&PublicKey::from(*signing_key.verifying_key()))
+        .is_err());
+}
+
+#[test]
+fn test_ed25519_verification_tolerates_small_order_components() {
+    use curve25519_dalek::constants::ED25519_BASEPOINT_POINT;
+    use curve25519_dalek::edwards::CompressedEdwardsY;
+    use curve25519_dalek::Scalar;
+    use sha2::{Digest, Sha512};
+
+    // R carries a point of order 4, which only the cofactored equation ignores. Batch
+    // verification ignores it too for most batches, so single verification has to agree.
+    let signing_key = SigningKey::generate(&mut OsRng);
+    let torsion = CompressedEdwardsY([0; 32]).decompress().unwrap();
+    let r = Scalar::from_bytes_mod_order([7; 32]);
+    let big_r = (ED25519_BASEPOINT_POINT * r + torsion).compress();
+    let message = b"reading";
+    let hash = Sha512::new()
+        .chain_update(big_r.as_bytes())
+        .chain_update(signing_key.verifying_key().as_bytes())
+        .chain_update(message)
+        .finalize();
+    let k = Scalar::from_bytes_mod_order_wide(&hash.into());
+    let s = r + k * signing_key.to_scalar();
+    let signature = ed25519_dalek::Signature::from_components(big_r.to_bytes(), s.to_bytes());
+
+    assert!(PublicKey::from(signing_key.verifying_key())
+        .verify_message(message, &Signature::Ed25519(signature))
+        .is_ok());
+    assert!(PublicKey::from(signing_key.verifying_key())
+        .verify_message(b"tampered", &Signature::Ed25519(signature))
+        .is_err());
+}
@@ -0,0 +1,111 @@
+use eco_weave::encryption::EncryptedPayload;
+use eco_weave::{Algorithm, PublicKey, SecretKey, Signer, Transaction};
+use ed25519_dalek::SigningKey;
+use rand::rngs::OsRng;
+
+#[test]
+fn test_only_recipients_can_decrypt() {
+    let farm_key = SecretKey::generate(Algorithm::Ed25519);
+    let lab_key = SecretKey::generate(Algorithm::Ed25519);
+    let relay_key = SecretKey::generate(Algorithm::Ed25519);
+    let farm = farm_key.public_key();
+    let lab = lab_key.public_key();
+
+    let envelope = EncryptedPayload::encrypt(
+        br#"{"soil_moisture": 31}"#,
+        &[("farm-1", &farm), ("lab", &lab)],
+    )
+    .unwrap();
+    let parsed = EncryptedPayload::from_bytes(&envelope.to_bytes()).unwrap();
+    assert_eq!(parsed, envelope);
+    assert_eq!(
+        parsed.recipients().collect::<Vec<_>>(),
+        vec!["farm-1", "lab"]
+    );
+
+    assert_eq!(
+        parsed.decrypt("farm-1", &farm_key).unwrap(),
+        br#"{"soil_moisture": 31}"#
+    );
+    assert_eq!(
+        parsed.decrypt("lab", &lab_key).unwrap(),
+        br#"{"soil_moisture": 31}"#
+    );
+    assert_eq!(
+        parsed.decrypt("farm-1", &relay_key).unwrap_err(),
+        "encryptionDecryptFailed"
+    );
+    assert_eq!(
+        parsed.decrypt("relay", &relay_key).unwrap_err(),
+        "encryptionNotRecipient: relay"
+    );
+}
+
+#[test]
+fn test_envelope_is_authenticated() {
+    let farm_key = SecretKey::generate(Algorithm::Ed25519);
+    let farm = farm_key.public_key();
+    let mut bytes = EncryptedPayload::encrypt(b"21.5", &[("farm-1", &farm)])
+        .unwrap()
+        .to_bytes();
+
+    let last = bytes.len() - 1;
+    bytes[last] ^= 0x01;
+    let tampered = EncryptedPayload::from_bytes(&bytes).unwrap();
+    assert_eq!(
+        tampered.decrypt("farm-1", &farm_key).unwrap_err(),
+        "encryptionDecryptFailed"
+    );
+
+    assert_eq!(
+        EncryptedPayload::from_bytes(&bytes[..20]).unwrap_err(),
+        "encryptionTruncated"
+    );
+    assert_eq!(
+        EncryptedPayload::from_bytes(b"{\"a\":1}").unwrap_err(),
+        "encryptionInvalidHeader"
+    );
+}
+
+#[test]
+fn test_encrypted_transaction() {
+    let issuer_key = SigningKey::generate(&mut OsRng);
+    let farm_key = SecretKey::generate(Algorithm::Ed25519);
+    let p256_key = p256::ecdsa::SigningKey::random(&mut OsRng);
+    let farm = farm_key.public_key();
+
+    assert_eq!(
+        Transaction::new("tx1", "21.5")
+            .unwrap()
+            .encrypt_for(&[("secure", &PublicKey::from(*p256_key.verifying_key()))])
+            .unwrap_err(),
+        "encryptionUnsupportedKey: secure"
+    );
+    let farm_envelope = Transaction::new("tx1", "21.5")
+        .unwrap()
+        .encrypt_for(&[("farm-1", &farm)])
+        .unwrap();
+    assert_eq!(
+        farm_envelope
+            .decrypt_payload("farm-1", &SecretKey::from(p256_key.clone()))
+            .unwrap_err(),
+        "encryptionUnsupportedKey: farm-1"
+    );
+
+    let mut tx = Transaction::new("tx1", "21.5")
+        .unwrap()
+        .encrypt_for(&[("farm-1", &farm)])
+        .unwrap();
+    tx.sign(&issuer_key);
+    assert!(tx.encrypted);
+    assert!(tx.validate().is_ok());
+    assert!(tx.validate_signature(&issuer_key.verifying_key()).is_ok());
+    assert_eq!(tx.decrypt_payload("farm-1", &farm_key).unwrap(), b"21.5");
+
+    tx.encrypted = false;
+    assert!(tx.validate_signature(&issuer_key.verifying_key()).is_err());
+    assert_eq!(
+        tx.decrypt_payload("farm-1", &farm_key).unwrap_err(),
+        "transactionNotEncrypted"
+    );
+}
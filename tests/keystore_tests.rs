@@ -0,0 +1,150 @@
+use eco_weave::keystore::KdfParams;
+use eco_weave::{Algorithm, Keystore, NodeIdentity, Signer, Tangle, Transaction};
+use rand::Rng;
+use std::path::{Path, PathBuf};
+
+fn keystore_dir() -> PathBuf {
+    std::env::temp_dir().join(format!(
+        "eco_weave-keystore-{}",
+        rand::thread_rng().gen::<u64>()
+    ))
+}
+
+// Cheap parameters so tests stay fast; real keystores use the defaults.
+fn test_keystore(dir: &Path) -> Keystore {
+    Keystore::open(dir).unwrap().with_kdf_params(KdfParams {
+        memory_kib: 64,
+        iterations: 1,
+        parallelism: 1,
+    })
+}
+
+#[test]
+fn test_save_and_load_identities() {
+    let dir = keystore_dir();
+    let keystore = test_keystore(&dir);
+
+    let station = keystore
+        .generate("station", "station-1", "correct horse")
+        .unwrap();
+    let secure = NodeIdentity::generate("secure-1", Algorithm::EcdsaP256);
+    keystore.save("secure", &secure, "battery staple").unwrap();
+
+    assert_eq!(keystore.list().unwrap(), vec!["secure", "station"]);
+    assert_eq!(
+        keystore.save("station", &secure, "x").unwrap_err(),
+        "keystoreIdentityExists: station"
+    );
+
+    let loaded = keystore.load("station", "correct horse").unwrap();
+    assert_eq!(loaded.node_id, "station-1");
+    assert_eq!(loaded.public_key(), station.public_key());
+
+    let loaded = keystore.load("secure", "battery staple").unwrap();
+    assert_eq!(loaded.public_key(), secure.public_key());
+    assert_eq!(loaded.algorithm(), Algorithm::EcdsaP256);
+
+    keystore.remove("secure").unwrap();
+    assert_eq!(keystore.list().unwrap(), vec!["station"]);
+    fs_cleanup(&dir);
+}
+
+#[test]
+fn test_wrong_passphrase_and_bad_names() {
+    let dir = keystore_dir();
+    let keystore = test_keystore(&dir);
+    keystore
+        .generate("station", "station-1", "correct horse")
+        .unwrap();
+
+    assert_eq!(
+        keystore.load("station", "wrong").unwrap_err(),
+        "keystoreWrongPassphrase"
+    );
+    assert_eq!(
+        keystore.load("missing", "wrong").unwrap_err(),
+        "keystoreUnknownIdentity: missing"
+    );
+    assert_eq!(
+        keystore.load("../etc/passwd", "x").unwrap_err(),
+        "keystoreInvalidName: ../etc/passwd"
+    );
+
+    keystore
+        .change_passphrase("station", "correct horse", "new passphrase")
+        .unwrap();
+    assert!(keystore.load("station", "correct horse").is_err());
+    assert!(keystore.load("station", "new passphrase").is_ok());
+
+    // The secret key never appears on disk in clear.
+    let identity = keystore.load("station", "new passphrase").unwrap();
+    let contents = std::fs::read_to_string(dir.join("station.key")).unwrap();
+    let secret: String = identity
+        .secret_key()
+        .to_bytes()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+    assert!(!contents.contains(&secret));
+    fs_cleanup(&dir);
+}
+
+#[test]
+fn test_identity_signs_for_its_node() {
+    let identity = NodeIdentity::generate("station-1", Algorithm::Ed25519);
+    let mut tangle = Tangle::new();
+    assert!(tangle
+        .try_add_transaction(identity.registration(0, 0).unwrap())
+        .is_ok());
+
+    let mut tx = Transaction::new("tx-1", "21.5")
+        .unwrap()
+        .with_issuer("station-1")
+        .with_sequence(1);
+    identity.sign_transaction(&mut tx).unwrap();
+    assert!(tangle.try_add_transaction(tx).is_ok());
+
+    let mut foreign = Transaction::new("tx-2", "21.5").unwrap();
+    assert_eq!(
+        identity.sign_transaction(&mut foreign).unwrap_err(),
+        "identityIssuerMismatch: tx-2 (identity: station-1)"
+    );
+}
+
+#[test]
+fn test_load_rejects_excessive_kdf_params() {
+    let dir = keystore_dir();
+    let keystore = test_keystore(&dir);
+    keystore
+        .generate("station", "station-1", "correct horse")
+        .unwrap();
+
+    // A tampered file must not be able to demand an unbounded key derivation.
+    let path = dir.join("station.key");
+    let contents = std::fs::read_to_string(&path).unwrap();
+    assert!(contents.contains("\"memory_kib\": 64"));
+    std::fs::write(
+        &path,
+        contents.replace("\"memory_kib\": 64", "\"memory_kib\": 4294967295"),
+    )
+    .unwrap();
+    assert_eq!(
+        keystore.load("station", "correct horse").unwrap_err(),
+        "keystoreInvalidKdf: memory_kib 4294967295 (max: 1048576)"
+    );
+
+    assert!(Keystore::open(&dir)
+        .unwrap()
+        .with_kdf_params(KdfParams {
+            iterations: 1_000,
+            ..KdfParams::default()
+        })
+        .generate("slow", "station-2", "x")
+        .unwrap_err()
+        .starts_with("keystoreInvalidKdf: iterations"));
+    fs_cleanup(&dir);
+}
+
+fn fs_cleanup(dir: &Path) {
+    let _ = std::fs::remove_dir_all(dir);
+}
@@ -1,4 +1,4 @@
-use eco_weave::Node;
+use eco_weave::{BoundingBox, Node, PublicKey, StationMetadata};
 use ed25519_dalek::SigningKey;
 
 #[test]
@@ -121,3 +121,109 @@ fn test_node_prevent_self_neighbor() {
     assert_eq!(node.neighbors.len(), 0);
     assert!(!node.is_neighbor("node1"));
 }
+
+#[test]
+fn test_station_metadata_is_signed_by_node_key() {
+    let signing_key = SigningKey::generate(&mut rand::rngs::OsRng);
+    let other_key = SigningKey::generate(&mut rand::rngs::OsRng);
+    let mut node = Node::new("station-1", signing_key.verifying_key());
+
+    let metadata = StationMetadata {
+        latitude: Some(48.85),
+        longitude: Some(2.35),
+        altitude: Some(35.0),
+        sensor_model: Some("BME280".to_string()),
+        installed_at: Some(1_700_000_000_000),
+        capabilities: vec!["temperature".to_string(), "humidity".to_string()],
+    };
+
+    let forged = metadata.sign("station-1", &other_key);
+    assert_eq!(
+        node.set_metadata(metadata.clone(), forged, 1_000)
+            .unwrap_err(),
+        "metadataInvalidSignature"
+    );
+
+    // A signature for another node id does not carry over.
+    let misdirected = metadata.sign("station-2", &signing_key);
+    assert!(node
+        .set_metadata(metadata.clone(), misdirected, 1_000)
+        .is_err());
+
+    let signature = metadata.sign("station-1", &signing_key);
+    assert!(node.set_metadata(metadata, signature, 1_000).is_ok());
+    assert_eq!(node.location(), Some((48.85, 2.35)));
+}
+
+#[test]
+fn test_station_metadata_validation() {
+    let signing_key = SigningKey::generate(&mut rand::rngs::OsRng);
+    let mut node = Node::new("station-1", signing_key.verifying_key());
+
+    let metadata = StationMetadata {
+        latitude: Some(91.0),
+        longitude: Some(0.0),
+        ..StationMetadata::default()
+    };
+    let signature = metadata.sign("station-1", &signing_key);
+    assert_eq!(
+        node.set_metadata(metadata, signature, 1_000).unwrap_err(),
+        "metadataInvalidLatitude: 91"
+    );
+
+    let metadata = StationMetadata {
+        latitude: Some(10.0),
+        ..StationMetadata::default()
+    };
+    assert_eq!(
+        metadata.validate().unwrap_err(),
+        "metadataIncompleteLocation"
+    );
+
+    let json = r#"{"latitude":1.5,"longitude":2.5,"altitude":null,"sensor_model":null,"installed_at":null,"capabilities":["rainfall"]}"#;
+    let parsed = StationMetadata::from_json(json).unwrap();
+    assert_eq!(parsed.location(), Some((1.5, 2.5)));
+    assert_eq!(parsed.to_json(), json);
+}
+
+#[test]
+fn test_bounding_box_across_antimeridian() {
+    let pacific = BoundingBox::new(-30.0, 170.0, 10.0, -170.0);
+    assert!(pacific.contains(0.0, 175.0));
+    assert!(pacific.contains(0.0, -175.0));
+    assert!(!pacific.contains(0.0, 0.0));
+    assert!(!pacific.contains(20.0, 175.0));
+}
+
+#[test]
+fn test_key_history_after_rotation_and_revocation() {
+    let old_key: PublicKey = SigningKey::generate(&mut rand::rngs::OsRng)
+        .verifying_key()
+        .into();
+    let new_key: PublicKey = SigningKey::generate(&mut rand::rngs::OsRng)
+        .verifying_key()
+        .into();
+    let mut node = Node::new("station-1", old_key);
+
+    node.rotate_key(new_key, 1_000).unwrap();
+    assert_eq!(node.verifying_key, new_key);
+    assert_eq!(node.key_at(999), Some(&old_key));
+    assert_eq!(node.key_at(1_000), Some(&new_key));
+
+    assert_eq!(
+        node.rotate_key(old_key, 2_000).unwrap_err(),
+        "keyRotationReused"
+    );
+    let later_key: PublicKey = SigningKey::generate(&mut rand::rngs::OsRng)
+        .verifying_key()
+        .into();
+    assert_eq!(
+        node.rotate_key(later_key, 500).unwrap_err(),
+        "keyRotationOutOfOrder: 500"
+    );
+
+    assert!(node.revoke_key(&new_key, 1_500));
+    assert_eq!(node.key_at(1_499), Some(&new_key));
+    assert_eq!(node.key_at(1_500), None);
+    assert!(!node.revoke_key(&later_key, 1_500));
+}
@@ -0,0 +1,50 @@
+use eco_weave::rate_limit::{RateLimitScope, TokenBucket};
+use eco_weave::{RateLimit, RateLimiter};
+
+#[test]
+fn test_token_bucket_refills_over_time() {
+    let limit = RateLimit::new(2, 0.5);
+    let mut bucket = TokenBucket::full(&limit, 0);
+
+    assert!(bucket.try_take(&limit, 0).is_ok());
+    assert!(bucket.try_take(&limit, 0).is_ok());
+    assert_eq!(bucket.try_take(&limit, 0), Err(2_000));
+    assert_eq!(bucket.try_take(&limit, 1_000), Err(1_000));
+    assert!(bucket.try_take(&limit, 2_000).is_ok());
+
+    // Refills never exceed the burst size.
+    assert!(bucket.try_take(&limit, 60_000).is_ok());
+    assert!(bucket.try_take(&limit, 60_000).is_ok());
+    assert!(bucket.try_take(&limit, 60_000).is_err());
+}
+
+#[test]
+fn test_rate_limiter_scopes_and_overrides() {
+    let mut limiter = RateLimiter::new()
+        .with_issuer_limit(RateLimit::per_minute(1, 1.0))
+        .with_neighbor_limit(RateLimit::new(5, 1.0));
+    limiter.set_node_limit("gateway", RateLimit::new(100, 10.0));
+
+    assert!(limiter.check_issuer("station-1", 0).is_ok());
+    let error = limiter.check_issuer("station-1", 0).unwrap_err();
+    assert_eq!(error.scope, RateLimitScope::Issuer);
+    assert_eq!(error.retry_after_ms, 60_000);
+    assert_eq!(
+        error.to_string(),
+        "transactionRateLimited: issuer station-1 (retry after: 60000 ms)"
+    );
+
+    // Buckets are independent per node and per scope.
+    assert!(limiter.check_issuer("station-2", 0).is_ok());
+    assert!(limiter.check_neighbor("station-1", 0).is_ok());
+    for _ in 0..10 {
+        assert!(limiter.check_issuer("gateway", 0).is_ok());
+    }
+
+    let metrics = limiter.metrics();
+    assert_eq!(metrics.allowed, 13);
+    assert_eq!(metrics.limited_issuers, 1);
+    assert_eq!(metrics.limited_by_node.get("station-1"), Some(&1));
+
+    assert!(RateLimiter::new().check_issuer("anyone", 0).is_ok());
+}
@@ -0,0 +1,87 @@
+use eco_weave::{NodeRegistration, StationMetadata, Transaction, TransactionKind};
+use ed25519_dalek::SigningKey;
+use rand::rngs::OsRng;
+
+#[test]
+fn test_registration_round_trips_through_json() {
+    let signing_key = SigningKey::generate(&mut OsRng);
+    let authority_key = SigningKey::generate(&mut OsRng);
+    let metadata = StationMetadata {
+        latitude: Some(43.6),
+        longitude: Some(1.44),
+        sensor_model: Some("SHT31".to_string()),
+        ..StationMetadata::default()
+    };
+    let signature = metadata.sign("station-1", &signing_key);
+
+    let registration = NodeRegistration::new("station-1", signing_key.verifying_key())
+        .with_metadata(metadata, signature)
+        .endorse("ministry", &authority_key);
+
+    let parsed = NodeRegistration::from_json(registration.to_json().as_bytes()).unwrap();
+    assert_eq!(parsed, registration);
+    assert!(parsed.validate().is_ok());
+    assert!(parsed
+        .verify_endorsement(&authority_key.verifying_key())
+        .is_ok());
+    assert!(parsed
+        .verify_endorsement(&signing_key.verifying_key())
+        .is_err());
+}
+
+#[test]
+fn test_registration_transaction_is_self_signed() {
+    let signing_key = SigningKey::generate(&mut OsRng);
+    let other_key = SigningKey::generate(&mut OsRng);
+    let registration = NodeRegistration::new("station-1", signing_key.verifying_key());
+
+    assert_eq!(
+        registration
+            .clone()
+            .into_transaction(&other_key, 0, 0)
+            .unwrap_err(),
+        "registrationKeyMismatch"
+    );
+
+    let tx = registration
+        .clone()
+        .into_transaction(&signing_key, 0, 0)
+        .unwrap();
+    assert_eq!(tx.kind, TransactionKind::Registration);
+    // The id depends only on the node and sequence, so the same registration built twice
+    // is recognised as a duplicate.
+    assert_eq!(tx.id, "register-station-1-0");
+    assert_eq!(
+        registration
+            .into_transaction(&signing_key, 0, 0)
+            .unwrap()
+            .id,
+        tx.id
+    );
+    assert_eq!(tx.issuer, "station-1");
+    assert!(tx.validate().is_ok());
+    assert!(tx.validate_signature(&signing_key.verifying_key()).is_ok());
+
+    // The kind is covered by the signature.
+    let mut tampered = tx.clone();
+    tampered.kind = TransactionKind::Data;
+    assert!(tampered
+        .validate_signature(&signing_key.verifying_key())
+        .is_err());
+
+    assert_eq!(
+        Transaction::new("tx1", "21.5").unwrap().kind,
+        TransactionKind::Data
+    );
+}
+
+#[test]
+fn test_registration_rejects_malformed_payload() {
+    assert!(NodeRegistration::from_json(b"{}")
+        .unwrap_err()
+        .starts_with("registrationInvalidPayload"));
+    assert_eq!(
+        NodeRegistration::from_json(br#"{"node_id":"a","public_key":"zz"}"#).unwrap_err(),
+        "invalidPublicKey"
+    );
+}
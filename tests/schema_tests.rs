@@ -0,0 +1,114 @@
+use eco_weave::validation::climate::ClimateReading;
+use eco_weave::validation::{FieldType, PayloadSchema, PayloadValidator};
+use eco_weave::{Tangle, Transaction};
+use ed25519_dalek::SigningKey;
+use rand::rngs::OsRng;
+
+const HYDROLOGY_SCHEMA: &str = r#"{
+    "name": "hydrology",
+    "fields": [
+        {"name": "water_level", "type": "number", "unit": "m", "min": 0.0, "max": 20.0, "required": true},
+        {"name": "flow_rate", "type": "number", "unit": "m3/s", "min": 0.0},
+        {"name": "gauge_status", "type": "string"}
+    ],
+    "allow_unknown_fields": false
+}"#;
+
+#[test]
+fn test_load_schema_from_json() {
+    let schema = PayloadSchema::from_json(HYDROLOGY_SCHEMA).unwrap();
+
+    assert_eq!(schema.name, "hydrology");
+    assert_eq!(schema.fields.len(), 3);
+    let water_level = schema.field("water_level").unwrap();
+    assert_eq!(water_level.field_type, FieldType::Number);
+    assert_eq!(water_level.unit.as_deref(), Some("m"));
+    assert!(water_level.required);
+
+    assert!(PayloadSchema::from_json(r#"{"name": "broken"}"#).is_err());
+}
+
+#[test]
+fn test_schema_validation_reports_every_problem() {
+    let schema = PayloadSchema::from_json(HYDROLOGY_SCHEMA).unwrap();
+
+    assert!(schema
+        .validate(r#"{"water_level": 3.2, "flow_rate": 12.0, "gauge_status": "ok"}"#.as_bytes())
+        .is_ok());
+
+    let report = schema.report(r#"{"flow_rate": -1.0, "gauge_status": 3, "turbidity": 4.0}"#);
+    let messages: Vec<String> = report.errors().map(|v| v.message()).collect();
+    assert_eq!(
+        messages,
+        vec![
+            "waterLevelMissing",
+            "flowRateOutOfRange:-1",
+            "gaugeStatusInvalidType:3",
+            "unknownField:turbidity"
+        ]
+    );
+}
+
+#[test]
+fn test_builtin_climate_schema_matches_climate_rules() {
+    let schema = PayloadSchema::climate();
+
+    assert_eq!(schema.name, "climate-json");
+    assert_eq!(
+        schema.field("pressure").unwrap().unit.as_deref(),
+        Some("hPa")
+    );
+    assert!(schema
+        .validate(r#"{"temperature": 25.6, "humidity": 60.0}"#.as_bytes())
+        .is_ok());
+    assert_eq!(
+        schema
+            .validate(r#"{"temperature": 200.0}"#.as_bytes())
+            .unwrap_err(),
+        "temperatureOutOfRange:200"
+    );
+    assert_eq!(
+        schema
+            .validate(r#"{"wind_speed": "fast"}"#.as_bytes())
+            .unwrap_err(),
+        "windSpeedInvalidType:fast"
+    );
+
+    // Registering it leaves the built-in validator, which also reads packed payloads, in place.
+    let mut tangle = Tangle::new();
+    tangle.register_schema(schema);
+    let packed = ClimateReading {
+        temperature: Some(21.5),
+        ..ClimateReading::default()
+    }
+    .to_packed();
+    assert!(tangle.validators["climate"].validate(&packed).is_ok());
+    assert!(tangle.validators["climate-json"].validate(&packed).is_err());
+}
+
+#[test]
+fn test_registered_schema_validates_transactions() {
+    let mut tangle = Tangle::new();
+    tangle.register_schema(PayloadSchema::from_json(HYDROLOGY_SCHEMA).unwrap());
+    let signing_key = SigningKey::generate(&mut OsRng);
+    tangle.add_node("gauge-1", signing_key.verifying_key());
+
+    let mut valid = Transaction::new("gauge-reading-1", r#"{"water_level": 3.2}"#)
+        .unwrap()
+        .with_issuer("gauge-1")
+        .with_sequence(1)
+        .with_schema("hydrology");
+    let mut invalid = Transaction::new("gauge-reading-2", r#"{"water_level": 31.0}"#)
+        .unwrap()
+        .with_issuer("gauge-1")
+        .with_sequence(2)
+        .with_schema("hydrology");
+    valid.sign(&signing_key);
+    invalid.sign(&signing_key);
+
+    assert!(tangle.add_transaction(valid));
+    assert_eq!(
+        tangle.try_add_transaction(invalid).unwrap_err().to_string(),
+        "transactionPayloadInvalid: waterLevelOutOfRange:31"
+    );
+}
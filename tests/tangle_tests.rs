@@ -1,6 +1,14 @@
-use eco_weave::{Tangle, Transaction};
+use eco_weave::rate_limit::RateLimitScope;
+use eco_weave::validation::climate::ClimateReading;
+use eco_weave::validation::{AnomalyDetector, PayloadValidator, TimestampPolicy, ValidationPolicy};
+use eco_weave::{
+    Algorithm, BoundingBox, KeyRotation, ManualClock, NodeRegistration, RateLimit, RateLimitError,
+    RateLimiter, Revocation, SecretKey, Signer, StationMetadata, Tangle, ThresholdPolicy,
+    TopologyEvent, Transaction, TransactionError,
+};
 use ed25519_dalek::SigningKey;
 use rand::rngs::OsRng;
+use std::sync::Arc;
 
 #[test]
 fn test_add_node() {
@@ -127,3 +135,1558 @@ fn test_get_snapshot() {
     assert!(txn_2_neighbors.contains(&"txn-3".to_string()));
     assert!(txn_3_neighbors.contains(&"txn-2".to_string()));
 }
+
+#[test]
+fn test_conflicting_readings_are_detected() {
+    let mut tangle = Tangle::new();
+    let signing_key = SigningKey::generate(&mut OsRng);
+    tangle.add_node("sensor-1", signing_key.verifying_key());
+
+    let mut reading_a = Transaction::new("reading-a", r#"{"temperature": 20.0}"#)
+        .unwrap()
+        .with_issuer("sensor-1")
+        .with_slot(42)
+        .with_sequence(1);
+    let mut reading_b = Transaction::new("reading-b", r#"{"temperature": 31.0}"#)
+        .unwrap()
+        .with_issuer("sensor-1")
+        .with_slot(42)
+        .with_sequence(2);
+    let mut other_slot = Transaction::new("reading-c", r#"{"temperature": 20.5}"#)
+        .unwrap()
+        .with_issuer("sensor-1")
+        .with_slot(43)
+        .with_sequence(3);
+    reading_a.sign(&signing_key);
+    reading_b.sign(&signing_key);
+    other_slot.sign(&signing_key);
+
+    assert!(tangle.add_transaction(reading_a));
+    assert!(tangle.add_transaction(reading_b));
+    assert!(tangle.add_transaction(other_slot));
+
+    assert!(tangle.is_conflicting("reading-a"));
+    assert_eq!(
+        tangle.get_conflicts("reading-a"),
+        vec!["reading-b".to_string()]
+    );
+    assert!(!tangle.is_conflicting("reading-c"));
+}
+
+#[test]
+fn test_resolve_conflicts_ignores_claimed_weight() {
+    let clock = Arc::new(ManualClock::new(10_000));
+    let mut tangle = Tangle::with_clock(clock);
+    let signing_key = SigningKey::generate(&mut OsRng);
+    tangle.add_node("sensor-1", signing_key.verifying_key());
+
+    let mut first = Transaction::new("first", "reading 1")
+        .unwrap()
+        .with_issuer("sensor-1")
+        .with_slot(7)
+        .with_sequence(1);
+    let mut inflated = Transaction::new("inflated", "reading 2")
+        .unwrap()
+        .with_issuer("sensor-1")
+        .with_slot(7)
+        .with_sequence(2);
+    first.timestamp = 1_000;
+    inflated.timestamp = 2_000;
+    first.sign(&signing_key);
+    inflated.sign(&signing_key);
+    // Weight is not signed, so a relay can set anything; the tangle recomputes it.
+    inflated.weight = u32::MAX;
+
+    assert!(tangle.add_transaction(first));
+    assert!(tangle.add_transaction(inflated));
+    assert_eq!(tangle.transactions["inflated"].weight, 1);
+
+    assert_eq!(tangle.resolve_conflicts(), vec!["inflated".to_string()]);
+    assert!(tangle.transactions["inflated"].rejected);
+    assert!(!tangle.transactions["first"].rejected);
+    assert!(tangle.transactions["first"].confirmed);
+
+    // Already resolved conflicts are not reported again.
+    assert!(tangle.resolve_conflicts().is_empty());
+}
+
+#[test]
+fn test_resolved_winner_is_not_overturned_by_backdated_arrival() {
+    let clock = Arc::new(ManualClock::new(10_000));
+    let mut tangle = Tangle::with_clock(clock);
+    let signing_key = SigningKey::generate(&mut OsRng);
+    tangle.add_node("sensor-1", signing_key.verifying_key());
+
+    for (id, sequence, timestamp) in [("a", 1, 3_000), ("b", 2, 2_000)] {
+        let mut transaction = Transaction::new(id, "reading")
+            .unwrap()
+            .with_issuer("sensor-1")
+            .with_slot(7)
+            .with_sequence(sequence);
+        transaction.timestamp = timestamp;
+        transaction.sign(&signing_key);
+        assert!(tangle.add_transaction(transaction));
+    }
+    assert_eq!(tangle.resolve_conflicts(), vec!["a".to_string()]);
+
+    let mut late = Transaction::new("c", "reading")
+        .unwrap()
+        .with_issuer("sensor-1")
+        .with_slot(7)
+        .with_sequence(3);
+    late.timestamp = 1_000;
+    late.sign(&signing_key);
+    assert!(tangle.add_transaction(late));
+
+    assert_eq!(tangle.resolve_conflicts(), vec!["c".to_string()]);
+    assert!(tangle.transactions["b"].confirmed);
+    assert!(!tangle.transactions["b"].rejected);
+}
+
+#[tokio::test]
+async fn test_random_walk_avoids_both_sides_of_conflict() {
+    let mut tangle = Tangle::new();
+    let signing_key = SigningKey::generate(&mut OsRng);
+    let verifying_key = signing_key.verifying_key();
+
+    for id in ["sensor-1", "tip", "side-a", "side-b"] {
+        tangle.add_node(id, verifying_key);
+    }
+    tangle.connect_nodes("side-a", "tip");
+    tangle.connect_nodes("side-a", "side-b");
+
+    let mut tip = Transaction::new("tip", "tip").unwrap();
+    let mut side_a = Transaction::new("side-a", "a")
+        .unwrap()
+        .with_issuer("sensor-1")
+        .with_slot(1)
+        .with_sequence(1);
+    let mut side_b = Transaction::new("side-b", "b")
+        .unwrap()
+        .with_issuer("sensor-1")
+        .with_slot(1)
+        .with_sequence(2);
+    for transaction in [&mut tip, &mut side_a, &mut side_b] {
+        transaction.sign(&signing_key);
+        transaction.weight = 1;
+        assert!(tangle.add_transaction(transaction.clone()));
+    }
+
+    for _ in 0..10 {
+        let selected = tangle.weighted_random_walk("side-a").await;
+        assert_eq!(selected, Some("tip".to_string()));
+    }
+}
+
+#[test]
+fn test_replayed_sequence_is_rejected() {
+    let mut tangle = Tangle::new();
+    let signing_key = SigningKey::generate(&mut OsRng);
+    tangle.add_node("sensor-1", signing_key.verifying_key());
+
+    let mut first = Transaction::new("reading-1", "first")
+        .unwrap()
+        .with_issuer("sensor-1")
+        .with_sequence(10);
+    let mut replay = Transaction::new("reading-2", "second")
+        .unwrap()
+        .with_issuer("sensor-1")
+        .with_sequence(10);
+    first.sign(&signing_key);
+    replay.sign(&signing_key);
+
+    assert!(tangle.try_add_transaction(first.clone()).is_ok());
+    assert_eq!(
+        tangle.try_add_transaction(first).unwrap_err().to_string(),
+        "transactionDuplicate: reading-1"
+    );
+    assert_eq!(
+        tangle.try_add_transaction(replay).unwrap_err().to_string(),
+        "transactionReplay: sequence 10"
+    );
+}
+
+#[test]
+fn test_sequence_window() {
+    let mut tangle = Tangle::new();
+    tangle.sequence_window = 4;
+    let signing_key = SigningKey::generate(&mut OsRng);
+    tangle.add_node("sensor-1", signing_key.verifying_key());
+
+    let mut add = |id: &str, sequence: u64| {
+        let mut transaction = Transaction::new(id, "reading")
+            .unwrap()
+            .with_issuer("sensor-1")
+            .with_sequence(sequence);
+        transaction.sign(&signing_key);
+        tangle.try_add_transaction(transaction)
+    };
+
+    assert!(add("reading-1", 10).is_ok());
+    // Out of order delivery within the window is fine.
+    assert!(add("reading-2", 12).is_ok());
+    assert!(add("reading-3", 11).is_ok());
+
+    assert!(add("reading-4", 17)
+        .unwrap_err()
+        .to_string()
+        .starts_with("transactionSequenceGap"));
+    assert!(add("reading-5", 8)
+        .unwrap_err()
+        .to_string()
+        .starts_with("transactionSequenceTooOld"));
+}
+
+#[test]
+fn test_add_transaction_uses_tangle_clock() {
+    let clock = Arc::new(ManualClock::new(5_000_000));
+    let mut tangle = Tangle::with_clock(clock.clone());
+    tangle.policy.timestamps = TimestampPolicy::new(2_000, Some(60_000));
+
+    let signing_key = SigningKey::generate(&mut OsRng);
+    tangle.add_node("sensor-1", signing_key.verifying_key());
+
+    let sign_at = |id: &str, sequence: u64, timestamp: u64| {
+        let mut transaction = Transaction::new(id, "reading")
+            .unwrap()
+            .with_issuer("sensor-1")
+            .with_sequence(sequence);
+        transaction.timestamp = timestamp;
+        transaction.sign(&signing_key);
+        transaction
+    };
+
+    // Slightly ahead of our clock, within the tolerated skew.
+    assert!(tangle.add_transaction(sign_at("reading-1", 1, 5_001_500)));
+
+    let stale = sign_at("reading-2", 2, 4_990_000);
+    clock.advance(120_000);
+    assert!(tangle
+        .try_add_transaction(stale)
+        .unwrap_err()
+        .to_string()
+        .starts_with("transactionTimestampTooOld"));
+}
+
+#[test]
+fn test_add_transaction_respects_policy() {
+    let policy = ValidationPolicy {
+        max_payload_size: 1024,
+        required_fields: vec!["temperature".to_string()],
+        ..ValidationPolicy::default()
+    };
+    let mut tangle = Tangle::with_policy(policy.clone());
+    let signing_key = SigningKey::generate(&mut OsRng);
+    tangle.add_node("gateway-1", signing_key.verifying_key());
+
+    let large_payload = format!(r#"{{"temperature": 21.5, "image": "{}"}}"#, "a".repeat(500));
+    let mut large = Transaction::new_with_policy("frame-1", large_payload, &policy)
+        .unwrap()
+        .with_issuer("gateway-1")
+        .with_sequence(1);
+    large.sign(&signing_key);
+    assert!(tangle.add_transaction(large));
+
+    let mut missing_field = Transaction::new("frame-2", r#"{"humidity": 40.0}"#)
+        .unwrap()
+        .with_issuer("gateway-1")
+        .with_sequence(2);
+    missing_field.sign(&signing_key);
+    assert_eq!(
+        tangle
+            .try_add_transaction(missing_field)
+            .unwrap_err()
+            .to_string(),
+        "transactionPayloadMissingField: temperature"
+    );
+
+    // Control transactions carry their own payload format.
+    let station_key = SigningKey::generate(&mut OsRng);
+    let registration = NodeRegistration::new("station-1", station_key.verifying_key())
+        .into_transaction(&station_key, 0, 0)
+        .unwrap();
+    assert!(tangle.try_add_transaction(registration).is_ok());
+}
+
+#[test]
+fn test_add_transaction_validates_climate_payload() {
+    let mut tangle = Tangle::new();
+    let signing_key = SigningKey::generate(&mut OsRng);
+    tangle.add_node("sensor-1", signing_key.verifying_key());
+
+    let mut valid = Transaction::new("reading-1", r#"{"temperature": 21.5}"#)
+        .unwrap()
+        .with_issuer("sensor-1")
+        .with_sequence(1)
+        .with_schema("climate");
+    let mut invalid = Transaction::new("reading-2", r#"{"temperature": 200.0}"#)
+        .unwrap()
+        .with_issuer("sensor-1")
+        .with_sequence(2)
+        .with_schema("climate");
+    let mut unknown = Transaction::new("reading-3", r#"{"flow": 2.0}"#)
+        .unwrap()
+        .with_issuer("sensor-1")
+        .with_sequence(3)
+        .with_schema("hydrology");
+    valid.sign(&signing_key);
+    invalid.sign(&signing_key);
+    unknown.sign(&signing_key);
+
+    assert!(tangle.try_add_transaction(valid).is_ok());
+    assert_eq!(
+        tangle.try_add_transaction(invalid).unwrap_err().to_string(),
+        "transactionPayloadInvalid: temperatureOutOfRange:200"
+    );
+    assert_eq!(
+        tangle.try_add_transaction(unknown).unwrap_err().to_string(),
+        "transactionUnknownSchema: hydrology"
+    );
+}
+
+#[derive(Debug)]
+struct HydrologyValidator;
+
+impl PayloadValidator for HydrologyValidator {
+    fn validate(&self, payload: &[u8]) -> Result<(), String> {
+        if payload.starts_with(b"{\"flow\"") {
+            Ok(())
+        } else {
+            Err("flowMissing".to_string())
+        }
+    }
+}
+
+#[test]
+fn test_register_custom_payload_validator() {
+    let mut tangle = Tangle::new();
+    tangle.register_validator("hydrology", HydrologyValidator);
+    let signing_key = SigningKey::generate(&mut OsRng);
+    tangle.add_node("gauge-1", signing_key.verifying_key());
+
+    let mut reading = Transaction::new("gauge-reading-1", r#"{"flow": 2.0}"#)
+        .unwrap()
+        .with_issuer("gauge-1")
+        .with_schema("hydrology");
+    reading.sign(&signing_key);
+
+    assert!(tangle.add_transaction(reading));
+}
+
+fn climate_reading(
+    signing_key: &SigningKey,
+    id: &str,
+    sequence: u64,
+    timestamp: u64,
+    payload: &str,
+) -> Transaction {
+    let mut transaction = Transaction::new(id, payload)
+        .unwrap()
+        .with_issuer("sensor-1")
+        .with_sequence(sequence)
+        .with_schema("climate");
+    transaction.timestamp = timestamp;
+    transaction.sign(signing_key);
+    transaction
+}
+
+#[test]
+fn test_rate_of_change_anomaly_is_flagged() {
+    let mut tangle = Tangle::with_clock(Arc::new(ManualClock::new(10_000_000)));
+    tangle.anomaly_detector = Some(AnomalyDetector::default());
+    let signing_key = SigningKey::generate(&mut OsRng);
+    tangle.add_node("sensor-1", signing_key.verifying_key());
+
+    let first = climate_reading(
+        &signing_key,
+        "reading-1",
+        1,
+        9_000_000,
+        r#"{"temperature": 20.0}"#,
+    );
+    let second = climate_reading(
+        &signing_key,
+        "reading-2",
+        2,
+        9_060_000,
+        r#"{"temperature": 95.0}"#,
+    );
+    let third = climate_reading(
+        &signing_key,
+        "reading-3",
+        3,
+        9_660_000,
+        r#"{"temperature": 92.0}"#,
+    );
+
+    assert!(tangle.add_transaction(first));
+    assert!(tangle.add_transaction(second));
+    assert!(tangle.add_transaction(third));
+
+    assert!(!tangle.is_anomalous("reading-1"));
+    assert!(tangle.is_anomalous("reading-2"));
+    assert!(!tangle.is_anomalous("reading-3"));
+
+    let anomaly = &tangle.anomalies["reading-2"][0];
+    assert_eq!(anomaly.code, "temperatureRateExceeded");
+    assert_eq!(anomaly.allowed, Some((15.0, 25.0)));
+}
+
+#[test]
+fn test_anomalies_can_be_rejected() {
+    let mut tangle = Tangle::with_clock(Arc::new(ManualClock::new(10_000_000)));
+    tangle.anomaly_detector = Some(AnomalyDetector {
+        stuck_after: Some(3),
+        reject: true,
+        ..AnomalyDetector::default()
+    });
+    let signing_key = SigningKey::generate(&mut OsRng);
+    tangle.add_node("sensor-1", signing_key.verifying_key());
+
+    for (sequence, timestamp) in [(1, 9_000_000), (2, 9_060_000)] {
+        let reading = climate_reading(
+            &signing_key,
+            &format!("reading-{}", sequence),
+            sequence,
+            timestamp,
+            r#"{"humidity": 41.0}"#,
+        );
+        assert!(tangle.add_transaction(reading));
+    }
+
+    let stuck = climate_reading(
+        &signing_key,
+        "reading-3",
+        3,
+        9_120_000,
+        r#"{"humidity": 41.0}"#,
+    );
+    assert_eq!(
+        tangle.try_add_transaction(stuck).unwrap_err().to_string(),
+        "transactionAnomalous: humidityStuck:41"
+    );
+}
+
+#[test]
+fn test_anomaly_history_is_bounded_per_issuer() {
+    let mut tangle = Tangle::with_clock(Arc::new(ManualClock::new(10_000_000)));
+    tangle.anomaly_detector = Some(AnomalyDetector {
+        history: 3,
+        ..AnomalyDetector::default()
+    });
+    let signing_key = SigningKey::generate(&mut OsRng);
+    tangle.add_node("sensor-1", signing_key.verifying_key());
+
+    // Delivered out of order; the history stays sorted by timestamp.
+    for sequence in [1, 2, 3, 5, 4] {
+        let reading = climate_reading(
+            &signing_key,
+            &format!("reading-{}", sequence),
+            sequence,
+            9_000_000 + sequence * 60_000,
+            &format!(r#"{{"temperature": {}.0}}"#, 20 + sequence),
+        );
+        assert!(tangle.add_transaction(reading));
+    }
+
+    let ids: Vec<&str> = tangle.recent_readings["sensor-1"]
+        .iter()
+        .map(|(_, id, _)| id.as_str())
+        .collect();
+    assert_eq!(ids, ["reading-3", "reading-4", "reading-5"]);
+    assert!(tangle.anomalies.is_empty());
+}
+
+#[test]
+fn test_add_packed_climate_transaction() {
+    let mut tangle = Tangle::new();
+    let signing_key = SigningKey::generate(&mut OsRng);
+    tangle.add_node("lora-1", signing_key.verifying_key());
+
+    let reading = ClimateReading {
+        temperature: Some(18.5),
+        humidity: Some(71.0),
+        ..ClimateReading::default()
+    };
+    let mut transaction = Transaction::new("lora-reading-1", reading.to_packed())
+        .unwrap()
+        .with_issuer("lora-1")
+        .with_schema("climate");
+    transaction.sign(&signing_key);
+
+    assert!(transaction.payload_str().is_none());
+    assert!(tangle.try_add_transaction(transaction).is_ok());
+}
+
+#[test]
+fn test_required_fields_apply_to_packed_payloads() {
+    let mut tangle = Tangle::with_policy(ValidationPolicy {
+        required_fields: vec!["temperature".to_string()],
+        ..ValidationPolicy::default()
+    });
+    let signing_key = SigningKey::generate(&mut OsRng);
+    tangle.add_node("lora-1", signing_key.verifying_key());
+
+    let packed = |id: &str, sequence: u64, reading: ClimateReading| {
+        let mut transaction = Transaction::new(id, reading.to_packed())
+            .unwrap()
+            .with_issuer("lora-1")
+            .with_sequence(sequence)
+            .with_schema("climate");
+        transaction.sign(&signing_key);
+        transaction
+    };
+
+    let complete = ClimateReading {
+        temperature: Some(18.5),
+        ..ClimateReading::default()
+    };
+    assert!(tangle
+        .try_add_transaction(packed("lora-reading-1", 1, complete))
+        .is_ok());
+
+    let incomplete = ClimateReading {
+        humidity: Some(71.0),
+        ..ClimateReading::default()
+    };
+    assert_eq!(
+        tangle
+            .try_add_transaction(packed("lora-reading-2", 2, incomplete))
+            .unwrap_err()
+            .to_string(),
+        "transactionPayloadMissingField: temperature"
+    );
+}
+
+#[test]
+fn test_nodes_within_bounding_box() {
+    let mut tangle = Tangle::new();
+    let stations = [
+        ("paris", 48.85, 2.35),
+        ("lyon", 45.76, 4.84),
+        ("oslo", 59.91, 10.75),
+    ];
+    let mut keys = Vec::new();
+    for (id, latitude, longitude) in stations {
+        let signing_key = SigningKey::generate(&mut OsRng);
+        tangle.add_node(id, signing_key.verifying_key());
+        let metadata = StationMetadata {
+            latitude: Some(latitude),
+            longitude: Some(longitude),
+            ..StationMetadata::default()
+        };
+        let signature = metadata.sign(id, &signing_key);
+        tangle.set_node_metadata(id, metadata, signature).unwrap();
+        keys.push(signing_key);
+    }
+    tangle.add_node("no-location", keys[0].verifying_key());
+
+    let france = BoundingBox::new(41.0, -5.0, 51.5, 9.6);
+    let ids: Vec<&str> = tangle
+        .nodes_within(&france)
+        .iter()
+        .map(|node| node.id.as_str())
+        .collect();
+    assert_eq!(ids, vec!["lyon", "paris"]);
+
+    let metadata = StationMetadata::default();
+    let signature = metadata.sign("ghost", &keys[0]);
+    assert_eq!(
+        tangle
+            .set_node_metadata("ghost", metadata, signature)
+            .unwrap_err(),
+        "nodeUnknown: ghost"
+    );
+}
+
+#[test]
+fn test_registration_transaction_adds_node() {
+    let mut tangle = Tangle::new();
+    let signing_key = SigningKey::generate(&mut OsRng);
+    let registration = NodeRegistration::new("station-1", signing_key.verifying_key());
+    let tx = registration.into_transaction(&signing_key, 0, 0).unwrap();
+
+    assert!(tangle.try_add_transaction(tx.clone()).is_ok());
+    assert_eq!(
+        tangle.get_verifying_key("station-1"),
+        Some(&signing_key.verifying_key().into())
+    );
+    assert!(tx.id.starts_with("register-station-1-"));
+    assert!(tangle.transactions.contains_key(&tx.id));
+
+    // The registered node can now issue data transactions.
+    let mut data = Transaction::new("reading-1", "21.5")
+        .unwrap()
+        .with_issuer("station-1")
+        .with_sequence(1);
+    data.sign(&signing_key);
+    assert!(tangle.try_add_transaction(data).is_ok());
+
+    // A later registration of the same id with another key is refused.
+    let other_key = SigningKey::generate(&mut OsRng);
+    let mut takeover = NodeRegistration::new("station-1", other_key.verifying_key())
+        .into_transaction(&other_key, 0, 0)
+        .unwrap();
+    takeover.id = "register-station-1-again".to_string();
+    takeover.timestamp = tx.timestamp + 1;
+    takeover.sign(&other_key);
+    assert_eq!(
+        tangle
+            .try_add_transaction(takeover)
+            .unwrap_err()
+            .to_string(),
+        "registrationNodeExists: station-1"
+    );
+}
+
+#[test]
+fn test_registration_requires_authority_endorsement() {
+    let mut tangle = Tangle::new();
+    let authority_key = SigningKey::generate(&mut OsRng);
+    tangle.add_authority("ministry", authority_key.verifying_key());
+    tangle.require_endorsement = true;
+
+    let signing_key = SigningKey::generate(&mut OsRng);
+    let unendorsed = NodeRegistration::new("station-1", signing_key.verifying_key())
+        .into_transaction(&signing_key, 0, 0)
+        .unwrap();
+    assert_eq!(
+        tangle
+            .try_add_transaction(unendorsed)
+            .unwrap_err()
+            .to_string(),
+        "registrationNotEndorsed"
+    );
+
+    let rogue_key = SigningKey::generate(&mut OsRng);
+    let forged = NodeRegistration::new("station-2", signing_key.verifying_key())
+        .endorse("ministry", &rogue_key)
+        .into_transaction(&signing_key, 0, 0)
+        .unwrap();
+    assert_eq!(
+        tangle.try_add_transaction(forged).unwrap_err().to_string(),
+        "registrationInvalidEndorsement: ministry"
+    );
+
+    let endorsed = NodeRegistration::new("station-3", signing_key.verifying_key())
+        .endorse("ministry", &authority_key)
+        .into_transaction(&signing_key, 0, 0)
+        .unwrap();
+    assert!(tangle.try_add_transaction(endorsed).is_ok());
+    assert!(tangle.get_node("station-3").is_some());
+}
+
+#[test]
+fn test_competing_registrations_settle_on_the_same_owner() {
+    let authority_key = SigningKey::generate(&mut OsRng);
+    let owner_key = SigningKey::generate(&mut OsRng);
+    let squatter_key = SigningKey::generate(&mut OsRng);
+    let registration = |key: &SigningKey, endorsed: bool, timestamp: u64| {
+        let mut registration = NodeRegistration::new("station-1", key.verifying_key());
+        if endorsed {
+            registration = registration.endorse("ministry", &authority_key);
+        }
+        let mut tx = registration.into_transaction(key, 0, 0).unwrap();
+        tx.id = format!("register-station-1-{}", timestamp);
+        tx.timestamp = timestamp;
+        tx.sign(key);
+        tx
+    };
+    let squatted = registration(&squatter_key, false, 2_000);
+    let endorsed = registration(&owner_key, true, 3_000);
+    let backdated = registration(&squatter_key, false, 1_000);
+
+    let tangle_with = |order: Vec<&Transaction>| {
+        let clock = Arc::new(ManualClock::new(10_000));
+        let mut tangle = Tangle::with_clock(clock);
+        tangle.add_authority("ministry", authority_key.verifying_key());
+        for tx in order {
+            let _ = tangle.try_add_transaction(tx.clone());
+        }
+        tangle
+    };
+
+    let mut tangle = tangle_with(vec![&squatted]);
+    assert!(tangle.add_transaction(signed_reading(
+        "squat-1",
+        "station-1",
+        1,
+        2_500,
+        &squatter_key
+    )));
+    // The endorsed registration takes over, and the squatter's transactions go with it.
+    assert!(tangle.try_add_transaction(endorsed.clone()).is_ok());
+    assert_eq!(
+        tangle.get_verifying_key("station-1"),
+        Some(&owner_key.verifying_key().into())
+    );
+    assert!(tangle.transactions[&squatted.id].rejected);
+    assert!(tangle.transactions["squat-1"].rejected);
+    assert_eq!(
+        tangle
+            .try_add_transaction(backdated.clone())
+            .unwrap_err()
+            .to_string(),
+        "registrationNodeExists: station-1"
+    );
+    assert!(tangle.add_transaction(signed_reading("tx-1", "station-1", 1, 3_500, &owner_key)));
+
+    // Whatever order the registrations arrive in, the owner is the same.
+    let reversed = tangle_with(vec![&backdated, &endorsed, &squatted]);
+    assert_eq!(
+        reversed.get_verifying_key("station-1"),
+        Some(&owner_key.verifying_key().into())
+    );
+    let unendorsed = tangle_with(vec![&squatted, &backdated]);
+    assert_eq!(unendorsed.registrations["station-1"], backdated.id);
+}
+
+fn signed_reading(
+    id: &str,
+    issuer: &str,
+    sequence: u64,
+    timestamp: u64,
+    key: &SigningKey,
+) -> Transaction {
+    let mut tx = Transaction::new(id, "21.5")
+        .unwrap()
+        .with_issuer(issuer)
+        .with_sequence(sequence);
+    tx.timestamp = timestamp;
+    tx.sign(key);
+    tx
+}
+
+#[test]
+fn test_key_rotation_is_signed_by_old_key() {
+    let clock = Arc::new(ManualClock::new(10_000));
+    let mut tangle = Tangle::with_clock(clock.clone());
+    let old_key = SigningKey::generate(&mut OsRng);
+    let new_key = SigningKey::generate(&mut OsRng);
+    tangle.add_node("station-1", old_key.verifying_key());
+
+    let forged = KeyRotation::new("station-1", new_key.verifying_key())
+        .into_transaction(&new_key, 1, 0)
+        .unwrap();
+    assert!(tangle.try_add_transaction(forged).is_err());
+
+    let mut rotation = KeyRotation::new("station-1", new_key.verifying_key())
+        .into_transaction(&old_key, 1, 0)
+        .unwrap();
+    rotation.timestamp = 5_000;
+    rotation.sign(&old_key);
+    assert!(tangle.try_add_transaction(rotation).is_ok());
+    assert_eq!(
+        tangle.get_verifying_key("station-1"),
+        Some(&new_key.verifying_key().into())
+    );
+
+    // Readings are checked against the key valid at their own timestamp.
+    assert!(tangle.add_transaction(signed_reading("tx-1", "station-1", 2, 4_000, &old_key)));
+    assert!(!tangle.add_transaction(signed_reading("tx-2", "station-1", 3, 6_000, &old_key)));
+    assert!(tangle.add_transaction(signed_reading("tx-3", "station-1", 3, 6_000, &new_key)));
+}
+
+#[test]
+fn test_revocation_rejects_transactions_after_compromise() {
+    let clock = Arc::new(ManualClock::new(10_000));
+    let mut tangle = Tangle::with_clock(clock.clone());
+    let authority_key = SigningKey::generate(&mut OsRng);
+    tangle.add_authority("ministry", authority_key.verifying_key());
+    let leaked_key = SigningKey::generate(&mut OsRng);
+    let replacement_key = SigningKey::generate(&mut OsRng);
+    tangle.add_node("station-1", leaked_key.verifying_key());
+
+    assert!(tangle.add_transaction(signed_reading("tx-1", "station-1", 1, 3_000, &leaked_key)));
+    assert!(tangle.add_transaction(signed_reading("tx-2", "station-1", 2, 7_000, &leaked_key)));
+
+    let rogue_key = SigningKey::generate(&mut OsRng);
+    let mut forged = Revocation::new("station-1", leaked_key.verifying_key(), 5_000)
+        .into_transaction("ministry", &rogue_key, 1, 0)
+        .unwrap();
+    forged.timestamp = 9_000;
+    forged.sign(&rogue_key);
+    assert_eq!(
+        tangle.try_add_transaction(forged).unwrap_err().to_string(),
+        "Invalid signature"
+    );
+
+    let mut revocation = Revocation::new("station-1", leaked_key.verifying_key(), 5_000)
+        .with_replacement(replacement_key.verifying_key())
+        .into_transaction("ministry", &authority_key, 1, 0)
+        .unwrap();
+    revocation.timestamp = 9_000;
+    revocation.sign(&authority_key);
+    assert!(tangle.try_add_transaction(revocation).is_ok());
+
+    assert!(!tangle.transactions["tx-1"].rejected);
+    assert!(tangle.transactions["tx-2"].rejected);
+    assert!(tangle
+        .try_add_transaction(signed_reading("tx-3", "station-1", 3, 8_000, &leaked_key))
+        .is_err());
+    assert!(tangle.add_transaction(signed_reading(
+        "tx-4",
+        "station-1",
+        3,
+        8_000,
+        &replacement_key
+    )));
+}
+
+fn signed_rotation(
+    node_id: &str,
+    new_key: &SigningKey,
+    sequence: u64,
+    timestamp: u64,
+    current_key: &SigningKey,
+) -> Transaction {
+    let mut rotation = KeyRotation::new(node_id, new_key.verifying_key())
+        .into_transaction(current_key, sequence, 0)
+        .unwrap();
+    rotation.timestamp = timestamp;
+    rotation.sign(current_key);
+    rotation
+}
+
+fn signed_revocation(
+    revocation: Revocation,
+    sequence: u64,
+    timestamp: u64,
+    authority_key: &SigningKey,
+) -> Transaction {
+    let mut revocation = revocation
+        .into_transaction("ministry", authority_key, sequence, 0)
+        .unwrap();
+    revocation.timestamp = timestamp;
+    revocation.sign(authority_key);
+    revocation
+}
+
+#[test]
+fn test_revoked_key_cannot_rotate_with_backdated_rotation() {
+    let clock = Arc::new(ManualClock::new(10_000));
+    let mut tangle = Tangle::with_clock(clock.clone());
+    let authority_key = SigningKey::generate(&mut OsRng);
+    tangle.add_authority("ministry", authority_key.verifying_key());
+    let leaked_key = SigningKey::generate(&mut OsRng);
+    let attacker_key = SigningKey::generate(&mut OsRng);
+    tangle.add_node("station-1", leaked_key.verifying_key());
+
+    let revocation = Revocation::new("station-1", leaked_key.verifying_key(), 5_000);
+    assert!(tangle
+        .try_add_transaction(signed_revocation(revocation, 1, 9_000, &authority_key))
+        .is_ok());
+
+    let backdated = signed_rotation("station-1", &attacker_key, 1, 4_000, &leaked_key);
+    assert_eq!(
+        tangle
+            .try_add_transaction(backdated)
+            .unwrap_err()
+            .to_string(),
+        "keyRotationRevokedKey: station-1"
+    );
+    assert!(!tangle.add_transaction(signed_reading("tx-1", "station-1", 2, 9_000, &attacker_key)));
+}
+
+#[test]
+fn test_revocation_undoes_rotation_signed_after_compromise() {
+    let clock = Arc::new(ManualClock::new(10_000));
+    let mut tangle = Tangle::with_clock(clock.clone());
+    let authority_key = SigningKey::generate(&mut OsRng);
+    tangle.add_authority("ministry", authority_key.verifying_key());
+    let leaked_key = SigningKey::generate(&mut OsRng);
+    let attacker_key = SigningKey::generate(&mut OsRng);
+    let chained_key = SigningKey::generate(&mut OsRng);
+    let replacement_key = SigningKey::generate(&mut OsRng);
+    tangle.add_node("station-1", leaked_key.verifying_key());
+
+    let rotation = signed_rotation("station-1", &attacker_key, 1, 6_000, &leaked_key);
+    assert!(tangle.try_add_transaction(rotation.clone()).is_ok());
+    let chained = signed_rotation("station-1", &chained_key, 2, 7_000, &attacker_key);
+    assert!(tangle.try_add_transaction(chained.clone()).is_ok());
+    assert!(tangle.add_transaction(signed_reading("tx-1", "station-1", 3, 6_500, &attacker_key)));
+
+    let revocation = Revocation::new("station-1", leaked_key.verifying_key(), 5_000)
+        .with_replacement(replacement_key.verifying_key());
+    assert!(tangle
+        .try_add_transaction(signed_revocation(revocation, 1, 9_000, &authority_key))
+        .is_ok());
+
+    assert!(tangle.transactions[&rotation.id].rejected);
+    assert!(tangle.transactions[&chained.id].rejected);
+    assert!(tangle.transactions["tx-1"].rejected);
+    for key in [&attacker_key, &chained_key] {
+        assert!(tangle
+            .try_add_transaction(signed_reading("tx-2", "station-1", 4, 9_500, key))
+            .is_err());
+    }
+    assert!(tangle.add_transaction(signed_reading(
+        "tx-2",
+        "station-1",
+        4,
+        9_500,
+        &replacement_key
+    )));
+}
+
+#[test]
+fn test_revoked_key_is_no_longer_current() {
+    let clock = Arc::new(ManualClock::new(10_000));
+    let mut tangle = Tangle::with_clock(clock.clone());
+    let authority_key = SigningKey::generate(&mut OsRng);
+    tangle.add_authority("ministry", authority_key.verifying_key());
+    let leaked_key = SigningKey::generate(&mut OsRng);
+    tangle.add_node("station-1", leaked_key.verifying_key());
+
+    let revocation = Revocation::new("station-1", leaked_key.verifying_key(), 5_000);
+    assert!(tangle
+        .try_add_transaction(signed_revocation(revocation, 1, 9_000, &authority_key))
+        .is_ok());
+
+    assert_eq!(tangle.get_verifying_key("station-1"), None);
+    let metadata = StationMetadata {
+        latitude: Some(48.85),
+        longitude: Some(2.35),
+        ..StationMetadata::default()
+    };
+    let signature = metadata.sign("station-1", &leaked_key);
+    assert_eq!(
+        tangle
+            .set_node_metadata("station-1", metadata, signature)
+            .unwrap_err(),
+        "nodeKeyRevoked: station-1"
+    );
+}
+
+#[test]
+fn test_current_key_falls_back_after_undone_rotation() {
+    let clock = Arc::new(ManualClock::new(10_000));
+    let mut tangle = Tangle::with_clock(clock.clone());
+    let authority_key = SigningKey::generate(&mut OsRng);
+    tangle.add_authority("ministry", authority_key.verifying_key());
+    let old_key = SigningKey::generate(&mut OsRng);
+    let current_key = SigningKey::generate(&mut OsRng);
+    let attacker_key = SigningKey::generate(&mut OsRng);
+    tangle.add_node("station-1", old_key.verifying_key());
+
+    let rotation = signed_rotation("station-1", &current_key, 1, 2_000, &old_key);
+    assert!(tangle.try_add_transaction(rotation).is_ok());
+    let hijack = signed_rotation("station-1", &attacker_key, 2, 6_000, &current_key);
+    assert!(tangle.try_add_transaction(hijack).is_ok());
+    assert_eq!(
+        tangle.get_verifying_key("station-1"),
+        Some(&attacker_key.verifying_key().into())
+    );
+
+    // Revoking the old key leaves the legitimate rotation alone.
+    let revocation = Revocation::new("station-1", old_key.verifying_key(), 3_000);
+    assert!(tangle
+        .try_add_transaction(signed_revocation(revocation, 1, 9_000, &authority_key))
+        .is_ok());
+    assert_eq!(
+        tangle.get_verifying_key("station-1"),
+        Some(&attacker_key.verifying_key().into())
+    );
+
+    // Revoking the current key undoes the hijack, and nothing valid is left.
+    let revocation = Revocation::new("station-1", current_key.verifying_key(), 5_000);
+    assert!(tangle
+        .try_add_transaction(signed_revocation(revocation, 2, 9_000, &authority_key))
+        .is_ok());
+    assert_eq!(tangle.get_verifying_key("station-1"), None);
+}
+
+#[test]
+fn test_remove_node_keeps_its_transactions() {
+    let mut tangle = Tangle::new();
+    let signing_key = SigningKey::generate(&mut OsRng);
+    for id in ["node1", "node2", "node3"] {
+        tangle.add_node(id, signing_key.verifying_key());
+    }
+    tangle.connect_nodes("node1", "node2");
+    tangle.connect_nodes("node2", "node3");
+    assert!(tangle.add_transaction(signed_reading("tx-1", "node2", 1, 1_000, &signing_key)));
+
+    assert!(tangle.remove_node("node2"));
+    assert!(!tangle.remove_node("node2"));
+    assert!(tangle.get_node("node2").is_none());
+    assert!(tangle.get_neighbors("node1").is_empty());
+    assert!(tangle.get_neighbors("node3").is_empty());
+
+    assert!(tangle.transactions.contains_key("tx-1"));
+    assert_eq!(
+        tangle
+            .try_add_transaction(signed_reading("tx-2", "node2", 2, 2_000, &signing_key))
+            .unwrap_err()
+            .to_string(),
+        "transactionUnknownIssuer: node2"
+    );
+}
+
+#[test]
+fn test_removed_node_can_register_again() {
+    let mut tangle = Tangle::new();
+    let signing_key = SigningKey::generate(&mut OsRng);
+    let register = |sequence: u64| {
+        NodeRegistration::new("station-1", signing_key.verifying_key())
+            .into_transaction(&signing_key, sequence, 0)
+            .unwrap()
+    };
+    let reading = |id: &str, sequence: u64| {
+        let mut tx = Transaction::new(id, "21.5")
+            .unwrap()
+            .with_issuer("station-1")
+            .with_sequence(sequence);
+        tx.sign(&signing_key);
+        tx
+    };
+
+    let first = register(0);
+    assert!(tangle.try_add_transaction(first.clone()).is_ok());
+    assert!(tangle.add_transaction(reading("tx-1", 60)));
+    assert!(tangle.add_transaction(reading("tx-2", 120)));
+
+    assert!(tangle.remove_node("station-1"));
+    assert!(!tangle.add_transaction(reading("tx-3", 121)));
+    // Replaying the old registration does not bring the node back.
+    assert_eq!(
+        tangle
+            .try_add_transaction(first.clone())
+            .unwrap_err()
+            .to_string(),
+        format!("transactionDuplicate: {}", first.id)
+    );
+
+    // The sequence window survives removal, so the returning node continues its counter.
+    assert!(tangle
+        .try_add_transaction(register(1))
+        .unwrap_err()
+        .to_string()
+        .starts_with("transactionSequenceTooOld"));
+    let second = register(121);
+    assert_ne!(second.id, first.id);
+    assert!(tangle.try_add_transaction(second).is_ok());
+    assert!(tangle.get_node("station-1").is_some());
+    assert!(!tangle.add_transaction(reading("tx-1-replay", 60)));
+    assert!(tangle.add_transaction(reading("tx-3", 122)));
+}
+
+#[test]
+fn test_disconnect_nodes() {
+    let mut tangle = Tangle::new();
+    let signing_key = SigningKey::generate(&mut OsRng);
+    tangle.add_node("node1", signing_key.verifying_key());
+    tangle.add_node("node2", signing_key.verifying_key());
+
+    assert!(!tangle.connect_nodes("node1", "node1"));
+    assert!(tangle.get_node("node1").is_some());
+
+    assert!(!tangle.disconnect_nodes("node1", "node2"));
+    tangle.connect_nodes("node1", "node2");
+    assert!(tangle.disconnect_nodes("node2", "node1"));
+    assert!(!tangle.get_node("node1").unwrap().is_neighbor("node2"));
+    assert!(!tangle.get_node("node2").unwrap().is_neighbor("node1"));
+}
+
+#[test]
+fn test_topology_events() {
+    let mut tangle = Tangle::new();
+    let mut events = tangle.subscribe();
+    let signing_key = SigningKey::generate(&mut OsRng);
+
+    tangle.add_node("node1", signing_key.verifying_key());
+    tangle.add_node("node2", signing_key.verifying_key());
+    tangle.connect_nodes("node1", "node2");
+    tangle.connect_nodes("node1", "node2");
+    tangle.remove_node("node1");
+
+    let mut received = Vec::new();
+    while let Ok(event) = events.try_recv() {
+        received.push(event);
+    }
+    assert_eq!(
+        received,
+        vec![
+            TopologyEvent::NodeAdded("node1".to_string()),
+            TopologyEvent::NodeAdded("node2".to_string()),
+            TopologyEvent::Connected("node1".to_string(), "node2".to_string()),
+            TopologyEvent::Disconnected("node1".to_string(), "node2".to_string()),
+            TopologyEvent::NodeRemoved("node1".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_add_batch_reports_each_transaction() {
+    let clock = Arc::new(ManualClock::new(10_000));
+    let mut tangle = Tangle::with_clock(clock);
+    let signing_key = SigningKey::generate(&mut OsRng);
+    let other_key = SigningKey::generate(&mut OsRng);
+    tangle.add_node("station-1", signing_key.verifying_key());
+
+    let mut transactions: Vec<Transaction> = (1..=20)
+        .map(|i| {
+            signed_reading(
+                &format!("tx-{}", i),
+                "station-1",
+                i,
+                1_000 + i,
+                &signing_key,
+            )
+        })
+        .collect();
+    transactions[4] = signed_reading("tx-5", "station-1", 5, 1_005, &other_key);
+    transactions[9].payload = b"tampered".to_vec();
+    transactions.push(signed_reading("tx-1", "station-1", 21, 1_021, &signing_key));
+
+    let results = tangle.add_batch(transactions);
+    assert_eq!(results.len(), 21);
+    for (index, result) in results.iter().enumerate() {
+        match index {
+            4 | 9 => assert_eq!(
+                result,
+                &Err(TransactionError::Rejected("Invalid signature".to_string()))
+            ),
+            20 => assert_eq!(
+                result,
+                &Err(TransactionError::Rejected(
+                    "transactionDuplicate: tx-1".to_string()
+                ))
+            ),
+            _ => assert!(result.is_ok(), "{}: {:?}", index, result),
+        }
+    }
+    assert_eq!(tangle.transactions.len(), 18);
+}
+
+#[test]
+fn test_add_batch_applies_registrations_in_order() {
+    let mut tangle = Tangle::new();
+    let signing_key = SigningKey::generate(&mut OsRng);
+
+    let registration = NodeRegistration::new("station-1", signing_key.verifying_key())
+        .into_transaction(&signing_key, 0, 0)
+        .unwrap();
+    let mut reading = Transaction::new("reading-1", "21.5")
+        .unwrap()
+        .with_issuer("station-1")
+        .with_sequence(1);
+    reading.sign(&signing_key);
+
+    let results = tangle.add_batch(vec![registration, reading]);
+    assert!(results.iter().all(Result::is_ok), "{:?}", results);
+    assert!(tangle.transactions.contains_key("reading-1"));
+}
+
+#[test]
+fn test_threshold_policy_requires_cosignatures() {
+    let mut tangle = Tangle::new();
+    let station_key = SigningKey::generate(&mut OsRng);
+    tangle.add_node("station-1", station_key.verifying_key());
+    let technician_keys: Vec<SigningKey> =
+        (0..3).map(|_| SigningKey::generate(&mut OsRng)).collect();
+    let technicians: Vec<String> = (0..3).map(|i| format!("tech-{}", i)).collect();
+    for (id, key) in technicians.iter().zip(&technician_keys) {
+        tangle.add_node(id.as_str(), key.verifying_key());
+    }
+    assert!(tangle
+        .set_threshold_policy(
+            "station-1",
+            Some(ThresholdPolicy::new(2, technicians.clone()).unwrap())
+        )
+        .is_ok());
+
+    let record = |id: &str, sequence: u64, cosigners: &[usize]| {
+        let mut tx = Transaction::new(id, "offset=0.3")
+            .unwrap()
+            .with_issuer("station-1")
+            .with_sequence(sequence);
+        tx.sign(&station_key);
+        for &i in cosigners {
+            tx.cosign(technicians[i].as_str(), &technician_keys[i]);
+        }
+        tx
+    };
+
+    assert_eq!(
+        tangle
+            .try_add_transaction(record("cal-1", 1, &[0]))
+            .unwrap_err()
+            .to_string(),
+        "transactionInsufficientCosignatures: 1/2"
+    );
+
+    let mut forged = record("cal-2", 1, &[0]);
+    forged.cosign("tech-1", &station_key);
+    assert_eq!(
+        tangle.try_add_transaction(forged).unwrap_err().to_string(),
+        "transactionInvalidCosignature: tech-1"
+    );
+
+    let mut unlisted = record("cal-3", 1, &[2]);
+    unlisted.cosign("station-1", &station_key);
+    assert!(tangle.try_add_transaction(unlisted).is_err());
+
+    assert!(tangle
+        .try_add_transaction(record("cal-4", 1, &[0, 2]))
+        .is_ok());
+}
+
+#[test]
+fn test_threshold_policy_is_validated() {
+    let mut tangle = Tangle::new();
+    let station_key = SigningKey::generate(&mut OsRng);
+    let technician_key = SigningKey::generate(&mut OsRng);
+    tangle.add_node("station-1", station_key.verifying_key());
+    tangle.add_node("tech-1", technician_key.verifying_key());
+    let signers = |ids: &[&str]| ids.iter().map(|id| id.to_string()).collect::<Vec<_>>();
+
+    // A signer listed twice would otherwise count twice towards the threshold.
+    assert_eq!(
+        ThresholdPolicy::new(2, signers(&["tech-1", "tech-1"])).unwrap_err(),
+        "thresholdDuplicateSigner: tech-1"
+    );
+    assert_eq!(
+        ThresholdPolicy::new(0, signers(&["tech-1"])).unwrap_err(),
+        "thresholdInvalid: 0 (signers: 1)"
+    );
+    assert_eq!(
+        tangle
+            .set_threshold_policy(
+                "station-1",
+                Some(ThresholdPolicy {
+                    threshold: 2,
+                    signers: signers(&["tech-1"]),
+                    schema: None,
+                })
+            )
+            .unwrap_err(),
+        "thresholdInvalid: 2 (signers: 1)"
+    );
+    assert_eq!(
+        tangle
+            .set_threshold_policy(
+                "station-1",
+                Some(ThresholdPolicy {
+                    threshold: 2,
+                    signers: signers(&["tech-1", "tech-1"]),
+                    schema: None,
+                })
+            )
+            .unwrap_err(),
+        "thresholdDuplicateSigner: tech-1"
+    );
+
+    // Even when assigned without validation, one cosignature cannot satisfy 2-of-2.
+    tangle.nodes.get_mut("station-1").unwrap().threshold = Some(ThresholdPolicy {
+        threshold: 2,
+        signers: signers(&["tech-1", "tech-1"]),
+        schema: None,
+    });
+    let mut tx = Transaction::new("cal-1", "offset=0.3")
+        .unwrap()
+        .with_issuer("station-1")
+        .with_sequence(1);
+    tx.sign(&station_key);
+    tx.cosign("tech-1", &technician_key);
+    assert_eq!(
+        tangle.try_add_transaction(tx).unwrap_err().to_string(),
+        "transactionInsufficientCosignatures: 1/2"
+    );
+}
+
+#[test]
+fn test_threshold_policy_scoped_to_schema() {
+    let mut tangle = Tangle::new();
+    let station_key = SigningKey::generate(&mut OsRng);
+    tangle.add_node("station-1", station_key.verifying_key());
+    tangle.register_validator("calibration", HydrologyValidator);
+    tangle
+        .set_threshold_policy(
+            "station-1",
+            Some(
+                ThresholdPolicy::new(1, vec!["tech-1".to_string()])
+                    .unwrap()
+                    .for_schema("calibration"),
+            ),
+        )
+        .unwrap();
+
+    assert!(tangle.add_transaction(signed_reading("tx-1", "station-1", 1, 1_000, &station_key)));
+
+    let mut tx = Transaction::new("cal-1", r#"{"flow": 1}"#)
+        .unwrap()
+        .with_issuer("station-1")
+        .with_sequence(2)
+        .with_schema("calibration");
+    tx.sign(&station_key);
+    assert_eq!(
+        tangle.try_add_transaction(tx).unwrap_err().to_string(),
+        "transactionInsufficientCosignatures: 0/1"
+    );
+}
+
+#[test]
+fn test_mixed_signature_schemes_share_a_tangle() {
+    let mut tangle = Tangle::new();
+    let ed25519_key = SigningKey::generate(&mut OsRng);
+    let p256_key = p256::ecdsa::SigningKey::random(&mut OsRng);
+    tangle.add_node("station-ed", ed25519_key.verifying_key());
+
+    let registration = NodeRegistration::new("station-p256", *p256_key.verifying_key())
+        .into_transaction(&p256_key, 0, 0)
+        .unwrap();
+    assert!(tangle.try_add_transaction(registration).is_ok());
+
+    let mut p256_reading = Transaction::new("tx-p256", "21.5")
+        .unwrap()
+        .with_issuer("station-p256")
+        .with_sequence(1);
+    p256_reading.sign(&p256_key);
+    p256_reading.cosign("station-ed", &ed25519_key);
+    tangle
+        .set_threshold_policy(
+            "station-p256",
+            Some(ThresholdPolicy::new(1, vec!["station-ed".to_string()]).unwrap()),
+        )
+        .unwrap();
+    assert!(tangle.try_add_transaction(p256_reading).is_ok());
+
+    let mut forged = Transaction::new("tx-forged", "21.5")
+        .unwrap()
+        .with_issuer("station-ed")
+        .with_sequence(1);
+    forged.sign(&p256_key);
+    assert!(tangle
+        .try_add_transaction(forged)
+        .unwrap_err()
+        .to_string()
+        .starts_with("signatureAlgorithmMismatch"));
+
+    let results = tangle.add_batch(vec![
+        signed_reading("tx-1", "station-ed", 2, 1_000, &ed25519_key),
+        {
+            let mut tx = Transaction::new("tx-2", "21.5")
+                .unwrap()
+                .with_issuer("station-p256")
+                .with_sequence(2);
+            tx.sign(&p256_key);
+            tx.cosign("station-ed", &ed25519_key);
+            tx
+        },
+        signed_reading("tx-3", "station-ed", 3, 1_000, &ed25519_key),
+        signed_reading(
+            "tx-4",
+            "station-ed",
+            4,
+            1_000,
+            &SigningKey::generate(&mut OsRng),
+        ),
+    ]);
+    assert!(results[..3].iter().all(Result::is_ok), "{:?}", results);
+    assert_eq!(
+        results[3],
+        Err(TransactionError::Rejected("Invalid signature".to_string()))
+    );
+}
+
+#[test]
+fn test_encrypted_payload_is_relayed_and_decrypted_by_recipient() {
+    let mut tangle = Tangle::new();
+    let station_key = SigningKey::generate(&mut OsRng);
+    let farm_key = SecretKey::generate(Algorithm::Ed25519);
+    let relay_key = SecretKey::generate(Algorithm::Ed25519);
+    tangle.add_node("station-1", station_key.verifying_key());
+    tangle.add_node("farm-1", farm_key.public_key());
+    tangle.add_node("relay", relay_key.public_key());
+
+    let farm = *tangle.get_verifying_key("farm-1").unwrap();
+    let mut tx = Transaction::new("tx-1", r#"{"soil_moisture": 31}"#)
+        .unwrap()
+        .with_issuer("station-1")
+        .with_schema("climate")
+        .encrypt_for(&[("farm-1", &farm)])
+        .unwrap();
+    tx.sign(&station_key);
+    assert!(tangle.try_add_transaction(tx).is_ok());
+
+    assert_eq!(
+        tangle.decrypt_payload("tx-1", "farm-1", &farm_key).unwrap(),
+        br#"{"soil_moisture": 31}"#
+    );
+    assert_eq!(
+        tangle
+            .decrypt_payload("tx-1", "farm-1", &relay_key)
+            .unwrap_err(),
+        "encryptionKeyMismatch: farm-1"
+    );
+    assert_eq!(
+        tangle
+            .decrypt_payload("tx-1", "relay", &relay_key)
+            .unwrap_err(),
+        "encryptionNotRecipient: relay"
+    );
+
+    // Recipients still validate the plaintext against the schema.
+    let mut invalid = Transaction::new("tx-2", r#"{"soil_moisture": 140}"#)
+        .unwrap()
+        .with_issuer("station-1")
+        .with_sequence(1)
+        .with_schema("climate")
+        .encrypt_for(&[("farm-1", &farm)])
+        .unwrap();
+    invalid.sign(&station_key);
+    assert!(tangle.try_add_transaction(invalid).is_ok());
+    assert!(tangle
+        .decrypt_payload("tx-2", "farm-1", &farm_key)
+        .unwrap_err()
+        .starts_with("transactionPayloadInvalid"));
+
+    // A revoked key no longer opens envelopes, even those sealed before the revocation.
+    tangle.nodes.get_mut("farm-1").unwrap().revoke_key(&farm, 0);
+    assert_eq!(
+        tangle
+            .decrypt_payload("tx-1", "farm-1", &farm_key)
+            .unwrap_err(),
+        "encryptionKeyRevoked: farm-1"
+    );
+}
+
+#[test]
+fn test_add_transaction_enforces_proof_of_work() {
+    let mut tangle = Tangle::with_policy(ValidationPolicy {
+        pow_difficulty: 8,
+        ..ValidationPolicy::default()
+    });
+    let signing_key = SigningKey::generate(&mut OsRng);
+    tangle.add_node("station-1", signing_key.verifying_key());
+
+    // Keep looking until the unmined transaction falls short, so the test is deterministic.
+    let mut lazy = Transaction::new("tx-1", "21.5")
+        .unwrap()
+        .with_issuer("station-1");
+    while lazy.work() >= 8 {
+        lazy = Transaction::new("tx-1", "21.5")
+            .unwrap()
+            .with_issuer("station-1");
+    }
+    lazy.sign(&signing_key);
+    assert!(tangle
+        .try_add_transaction(lazy.clone())
+        .unwrap_err()
+        .to_string()
+        .starts_with("transactionInsufficientWork"));
+
+    lazy.mine(8).unwrap();
+    lazy.sign(&signing_key);
+    assert!(tangle.try_add_transaction(lazy).is_ok());
+
+    // Control transactions are held to the same policy.
+    let station_key = SigningKey::generate(&mut OsRng);
+    let registration = NodeRegistration::new("station-2", station_key.verifying_key())
+        .into_transaction(&station_key, 0, 8)
+        .unwrap();
+    assert!(registration.work() >= 8);
+    assert!(tangle.try_add_transaction(registration).is_ok());
+}
+
+#[test]
+fn test_issuer_rate_limit_uses_tangle_clock() {
+    let clock = Arc::new(ManualClock::new(10_000));
+    let mut tangle = Tangle::with_clock(clock.clone());
+    tangle.rate_limiter = RateLimiter::new().with_issuer_limit(RateLimit::new(2, 1.0));
+    let signing_key = SigningKey::generate(&mut OsRng);
+    let other_key = SigningKey::generate(&mut OsRng);
+    tangle.add_node("station-1", signing_key.verifying_key());
+
+    // Forged transactions are refused before they can spend the issuer's tokens.
+    for i in 0..5 {
+        let forged = signed_reading(&format!("forged-{}", i), "station-1", i, 1_000, &other_key);
+        assert!(!tangle.add_transaction(forged));
+    }
+
+    assert!(tangle.add_transaction(signed_reading("tx-1", "station-1", 1, 1_000, &signing_key)));
+    assert!(tangle.add_transaction(signed_reading("tx-2", "station-1", 2, 1_000, &signing_key)));
+    assert_eq!(
+        tangle
+            .try_add_transaction(signed_reading("tx-3", "station-1", 3, 1_000, &signing_key))
+            .unwrap_err(),
+        TransactionError::RateLimited(RateLimitError {
+            scope: RateLimitScope::Issuer,
+            node_id: "station-1".to_string(),
+            retry_after_ms: 1_000,
+        })
+    );
+
+    clock.advance(1_000);
+    assert!(tangle.add_transaction(signed_reading("tx-3", "station-1", 3, 1_000, &signing_key)));
+    assert_eq!(tangle.rate_limiter.metrics().limited_issuers, 1);
+}
+
+#[test]
+fn test_replayed_invalid_transaction_does_not_drain_issuer_limit() {
+    let clock = Arc::new(ManualClock::new(10_000));
+    let mut tangle = Tangle::with_clock(clock);
+    tangle.rate_limiter = RateLimiter::new().with_issuer_limit(RateLimit::new(2, 0.1));
+    let signing_key = SigningKey::generate(&mut OsRng);
+    tangle.add_node("sensor-1", signing_key.verifying_key());
+
+    let out_of_range = climate_reading(&signing_key, "bad", 1, 1_000, r#"{"temperature":500}"#);
+    for _ in 0..5 {
+        assert!(tangle.try_add_transaction(out_of_range.clone()).is_err());
+    }
+
+    assert!(tangle.add_transaction(climate_reading(
+        &signing_key,
+        "tx-1",
+        2,
+        1_000,
+        r#"{"temperature":20}"#
+    )));
+    assert!(tangle.add_transaction(climate_reading(
+        &signing_key,
+        "tx-2",
+        3,
+        1_000,
+        r#"{"temperature":21}"#
+    )));
+    assert_eq!(tangle.rate_limiter.metrics().limited_issuers, 0);
+}
+
+#[test]
+fn test_neighbor_gossip_rate_limit() {
+    let clock = Arc::new(ManualClock::new(10_000));
+    let mut tangle = Tangle::with_clock(clock.clone());
+    tangle.rate_limiter = RateLimiter::new().with_neighbor_limit(RateLimit::new(1, 0.1));
+    let signing_key = SigningKey::generate(&mut OsRng);
+    tangle.add_node("station-1", signing_key.verifying_key());
+
+    let first = signed_reading("tx-1", "station-1", 1, 1_000, &signing_key);
+    assert!(tangle.receive_transaction("relay-a", first).is_ok());
+
+    let second = signed_reading("tx-2", "station-1", 2, 1_000, &signing_key);
+    match tangle.receive_transaction("relay-a", second.clone()) {
+        Err(TransactionError::RateLimited(error)) => {
+            assert_eq!(error.node_id, "relay-a");
+            assert_eq!(error.retry_after_ms, 10_000);
+        }
+        other => panic!("expected a rate limit, got {:?}", other),
+    }
+
+    assert!(tangle.receive_transaction("relay-b", second).is_ok());
+    assert_eq!(
+        tangle.receive_transaction(
+            "relay-b",
+            signed_reading("tx-2", "station-1", 2, 1_000, &signing_key)
+        ),
+        Err(TransactionError::RateLimited(RateLimitError {
+            scope: RateLimitScope::Neighbor,
+            node_id: "relay-b".to_string(),
+            retry_after_ms: 10_000,
+        }))
+    );
+
+    let metrics = tangle.rate_limiter.metrics();
+    assert_eq!(metrics.limited_neighbors, 2);
+    assert_eq!(metrics.limited_by_node.get("relay-a"), Some(&1));
+}
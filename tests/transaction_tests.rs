@@ -1,16 +1,16 @@
 #[cfg(test)]
 mod tests {
-    use eco_weave::Transaction;
+    use eco_weave::validation::policy::DEFAULT_MAX_PAYLOAD_SIZE as MAX_PAYLOAD_SIZE;
+    use eco_weave::validation::{IdPolicy, TimestampPolicy, ValidationPolicy};
+    use eco_weave::{ManualClock, SystemClock, Transaction};
     use ed25519_dalek::SigningKey;
     use rand::rngs::OsRng;
 
-    const MAX_PAYLOAD_SIZE: usize = 256;
-
     #[test]
     fn test_transaction_creation() {
         let tx = Transaction::new("tx1", r#"{"temperature": 25}"#).unwrap();
         assert_eq!(tx.id, "tx1");
-        assert_eq!(tx.payload, r#"{"temperature": 25}"#);
+        assert_eq!(tx.payload_str(), Some(r#"{"temperature": 25}"#));
         assert!(tx.timestamp > 0);
     }
 
@@ -91,4 +91,217 @@ mod tests {
 
         assert!(tx.validate_signature(&verifying_key2).is_err());
     }
+
+    #[test]
+    fn test_issuer_defaults_to_id() {
+        let tx = Transaction::new("sensor-1", "Payload").unwrap();
+        assert_eq!(tx.issuer, "sensor-1");
+        assert_eq!(tx.slot, None);
+
+        let tx = tx.with_issuer("station-9").with_slot(3);
+        assert_eq!(tx.issuer, "station-9");
+        assert_eq!(tx.slot, Some(3));
+    }
+
+    #[test]
+    fn test_signature_covers_slot_and_sequence() {
+        let signing_key = SigningKey::generate(&mut OsRng);
+        let verifying_key = signing_key.verifying_key();
+
+        let mut tx = Transaction::new("tx1", "Payload").unwrap().with_slot(1);
+        tx.sign(&signing_key);
+        tx.slot = Some(2);
+        assert!(tx.validate_signature(&verifying_key).is_err());
+
+        let mut tx = Transaction::new("tx1", "Payload").unwrap().with_sequence(1);
+        tx.sign(&signing_key);
+        tx.sequence = 2;
+        assert!(tx.validate_signature(&verifying_key).is_err());
+
+        let mut tx = Transaction::new("tx1", "Payload")
+            .unwrap()
+            .with_schema("climate");
+        tx.sign(&signing_key);
+        tx.schema = None;
+        assert!(tx.validate_signature(&verifying_key).is_err());
+    }
+
+    #[test]
+    fn test_signature_separates_fields_containing_delimiters() {
+        let signing_key = SigningKey::generate(&mut OsRng);
+        let verifying_key = signing_key.verifying_key();
+
+        let mut tx = Transaction::new("tx1", "Payload")
+            .unwrap()
+            .with_issuer("station:9");
+        tx.sign(&signing_key);
+        tx.id = "tx1:station".to_string();
+        tx.issuer = "9".to_string();
+        assert!(tx.validate_signature(&verifying_key).is_err());
+    }
+
+    #[test]
+    fn test_validate_timestamp_with_skew_tolerance() {
+        let clock = ManualClock::new(1_000_000);
+        let mut tx = Transaction::new("tx1", "Payload").unwrap();
+        tx.timestamp = 1_002_000;
+
+        let strict = ValidationPolicy::default();
+        let tolerant = ValidationPolicy {
+            timestamps: TimestampPolicy::new(5_000, None),
+            ..ValidationPolicy::default()
+        };
+
+        assert!(tx.validate_with(&strict, &clock).is_err());
+        assert!(tx.validate_with(&tolerant, &clock).is_ok());
+
+        clock.set(990_000);
+        let err = tx.validate_with(&tolerant, &clock).unwrap_err();
+        assert_eq!(err, "transactionTimestampInvalid: 1002000 (now: 990000)");
+    }
+
+    #[test]
+    fn test_validate_timestamp_max_age() {
+        let clock = ManualClock::new(1_000_000);
+        let policy = ValidationPolicy {
+            timestamps: TimestampPolicy::new(0, Some(60_000)),
+            ..ValidationPolicy::default()
+        };
+        let mut tx = Transaction::new("tx1", "Payload").unwrap();
+        tx.timestamp = 950_000;
+
+        assert!(tx.validate_with(&policy, &clock).is_ok());
+
+        clock.advance(20_000);
+        let err = tx.validate_with(&policy, &clock).unwrap_err();
+        assert!(err.starts_with("transactionTimestampTooOld"));
+    }
+
+    #[test]
+    fn test_custom_validation_policy() {
+        let policy = ValidationPolicy {
+            max_payload_size: 8,
+            id: IdPolicy {
+                min_length: 3,
+                max_length: Some(12),
+                allowed_symbols: vec!['-', '_'],
+            },
+            ..ValidationPolicy::default()
+        };
+
+        assert!(Transaction::new_with_policy("lora_node-1", "21.5", &policy).is_ok());
+        assert_eq!(
+            Transaction::new_with_policy("ab", "21.5", &policy).unwrap_err(),
+            "transactionInvalidIdLength"
+        );
+        assert_eq!(
+            Transaction::new_with_policy("lora-1", "123456789", &policy).unwrap_err(),
+            "transactionPayloadTooLarge"
+        );
+        // Underscores are not part of the default charset.
+        assert!(Transaction::new("lora_node-1", "21.5").is_err());
+    }
+
+    #[test]
+    fn test_binary_payload() {
+        let signing_key = SigningKey::generate(&mut OsRng);
+        let verifying_key = signing_key.verifying_key();
+
+        let mut tx = Transaction::new("tx1", vec![0xC1, 0x01, 0x00, 0xFF]).unwrap();
+        tx.sign(&signing_key);
+
+        assert!(tx.validate().is_ok());
+        assert!(tx.validate_signature(&verifying_key).is_ok());
+        assert_eq!(tx.payload_str(), None);
+
+        tx.payload[3] = 0xFE;
+        assert!(tx.validate_signature(&verifying_key).is_err());
+    }
+
+    #[test]
+    fn test_cosignatures_cover_transaction_data() {
+        let issuer_key = SigningKey::generate(&mut OsRng);
+        let technician_key = SigningKey::generate(&mut OsRng);
+
+        let mut tx = Transaction::new("calibration-1", "offset=0.3").unwrap();
+        tx.sign(&issuer_key);
+        tx.cosign("technician", &technician_key);
+        tx.cosign("technician", &technician_key);
+        assert_eq!(tx.cosignatures.len(), 1);
+
+        assert!(tx
+            .validate_cosignature("technician", &technician_key.verifying_key())
+            .is_ok());
+        assert_eq!(
+            tx.validate_cosignature("technician", &issuer_key.verifying_key())
+                .unwrap_err(),
+            "transactionInvalidCosignature: technician"
+        );
+        assert_eq!(
+            tx.validate_cosignature("supervisor", &technician_key.verifying_key())
+                .unwrap_err(),
+            "transactionMissingCosignature: supervisor"
+        );
+
+        // Cosigning does not disturb the issuer's signature.
+        assert!(tx.validate_signature(&issuer_key.verifying_key()).is_ok());
+        tx.payload = b"offset=9.9".to_vec();
+        assert!(tx
+            .validate_cosignature("technician", &technician_key.verifying_key())
+            .is_err());
+    }
+
+    #[test]
+    fn test_mine_proof_of_work() {
+        let signing_key = SigningKey::generate(&mut OsRng);
+        let policy = ValidationPolicy {
+            pow_difficulty: 10,
+            ..ValidationPolicy::default()
+        };
+
+        let mut tx = Transaction::new("tx1", "Payload").unwrap();
+        tx.mine(10).unwrap();
+        assert!(tx.work() >= 10);
+        tx.sign(&signing_key);
+        assert!(tx.validate_with(&policy, &SystemClock).is_ok());
+
+        // The nonce is signed, so the work cannot be grafted onto another transaction.
+        assert!(tx.validate_signature(&signing_key.verifying_key()).is_ok());
+        tx.payload = b"Other".to_vec();
+        assert!(tx.validate_signature(&signing_key.verifying_key()).is_err());
+    }
+
+    #[test]
+    fn test_mine_and_sign_keeps_work_for_p256() {
+        let signing_key = p256::ecdsa::SigningKey::random(&mut OsRng);
+        let policy = ValidationPolicy {
+            pow_difficulty: 8,
+            ..ValidationPolicy::default()
+        };
+
+        let mut tx = Transaction::new("tx1", "Payload").unwrap();
+        tx.mine_and_sign(8, &signing_key).unwrap();
+        assert!(tx.work() >= 8);
+        assert!(tx.validate_with(&policy, &SystemClock).is_ok());
+        assert!(tx.validate_signature(signing_key.verifying_key()).is_ok());
+    }
+
+    #[test]
+    fn test_pow_difficulty_is_capped() {
+        let mut tx = Transaction::new("tx1", "Payload").unwrap();
+        assert_eq!(
+            tx.mine(257).unwrap_err(),
+            "powDifficultyTooHigh: 257 (max: 256)"
+        );
+
+        let policy = ValidationPolicy {
+            pow_difficulty: 300,
+            ..ValidationPolicy::default()
+        };
+        tx.sign(&SigningKey::generate(&mut OsRng));
+        assert_eq!(
+            tx.validate_with(&policy, &SystemClock).unwrap_err(),
+            "powDifficultyTooHigh: 300 (max: 256)"
+        );
+    }
 }